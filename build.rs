@@ -0,0 +1,50 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Generates one test per example file in `examples/dayNN/`, so that
+/// adding an example and its answers is enough to have it run by
+/// `cargo test`. Examples whose answers file has a `# slow` line are
+/// ignored unless running `cargo test -- --ignored`.
+fn main() {
+    println!("cargo:rerun-if-changed=examples");
+
+    let mut tests = String::new();
+    let mut days: Vec<_> = fs::read_dir("examples")
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    days.sort();
+
+    for dir in days {
+        let name = dir.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let day = match name.strip_prefix("day").and_then(|day| day.parse::<u8>().ok()) {
+            Some(day) if dir.is_dir() => day,
+            _ => {
+                println!("cargo:warning=skipping examples/{}, expected a dayNN directory", name);
+                continue;
+            }
+        };
+
+        let mut files: Vec<_> = fs::read_dir(&dir).unwrap().flatten().map(|e| e.path()).collect();
+        files.sort();
+
+        for file in files.iter().filter(|f| f.extension().is_some_and(|ext| ext == "txt")) {
+            let name = file.file_stem().unwrap().to_str().unwrap();
+            let ident: String = name.chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+                .collect();
+
+            let slow = fs::read_to_string(file.with_extension("answers"))
+                .is_ok_and(|answers| answers.lines().any(|line| line.trim() == "# slow"));
+            let ignore = if slow { "#[ignore = \"slow\"]\n" } else { "" };
+
+            tests.push_str(&format!(
+                "#[test]\n{}fn day{:02}_{}() {{\n    check({}, {:?});\n}}\n\n",
+                ignore, day, ident, day, name,
+            ));
+        }
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("example_tests.rs");
+    fs::write(out, tests).unwrap();
+}
@@ -0,0 +1,142 @@
+//! Puzzle answers, which are numbers, text or ASCII art.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use num::BigInt;
+
+/// The answer to a puzzle part.
+#[derive(Clone, Debug)]
+pub enum Answer {
+    /// A number that fits in an `i128`
+    Int(i128),
+    /// A number that doesn't
+    Big(BigInt),
+    /// Anything else, on one line
+    Text(String),
+    /// Letters drawn as a grid of characters, one string per row
+    Art(Vec<String>),
+}
+
+impl Answer {
+    /// ASCII art from its rows.
+    pub fn art<I, S>(rows: I) -> Answer where I: IntoIterator<Item = S>, S: Into<String> {
+        Answer::Art(rows.into_iter().map(|row| row.into()).collect())
+    }
+
+    /// Single line representation used in answer files and tables. Art rows
+    /// are joined with a literal `\n`, which [`Answer::from_str`] reverses.
+    pub fn serialize(&self) -> String {
+        match self {
+            Answer::Int(v) => v.to_string(),
+            Answer::Big(v) => v.to_string(),
+            Answer::Text(s) => s.clone(),
+            Answer::Art(rows) => rows.join("\\n"),
+        }
+    }
+
+    /// The answer as it should be displayed, one string per line.
+    pub fn lines(&self) -> Vec<String> {
+        match self {
+            Answer::Art(rows) => rows.clone(),
+            _ => vec![self.serialize()],
+        }
+    }
+}
+
+/// Answers compare by their serialized form, so that an `Int` and a `Big`
+/// holding the same number are equal.
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.serialize() == other.serialize()
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Art(rows) => {
+                for row in rows {
+                    write!(f, "\n{}", row)?;
+                }
+                Ok(())
+            },
+            _ => write!(f, "{}", self.serialize()),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Answer, Infallible> {
+        let s = s.trim();
+        if s.contains("\\n") {
+            Ok(Answer::art(s.split("\\n")))
+        } else if let Ok(v) = s.parse::<i128>() {
+            Ok(Answer::Int(v))
+        } else if let Ok(v) = s.parse::<BigInt>() {
+            Ok(Answer::Big(v))
+        } else {
+            Ok(Answer::Text(s.to_string()))
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Answer {
+                    Answer::Int(v as i128)
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i128, i64, i32, u64, u32, u16, usize);
+
+impl From<BigInt> for Answer {
+    fn from(v: BigInt) -> Answer {
+        Answer::Big(v)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Text(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for answer in [
+            Answer::Int(-12),
+            Answer::Big("123456789012345678901234567890123456789012".parse().unwrap()),
+            Answer::from("FGHJ"),
+            Answer::art(["#..#", "####"]),
+        ] {
+            assert_eq!(answer.serialize().parse::<Answer>().unwrap(), answer);
+        }
+    }
+
+    #[test]
+    fn test_int_equals_big() {
+        assert_eq!(Answer::Int(26984457539), Answer::Big(BigInt::from(26984457539_i64)));
+        assert_ne!(Answer::Int(1), Answer::from("one"));
+    }
+}
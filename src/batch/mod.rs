@@ -0,0 +1,160 @@
+//! Running one day against many input files, each optionally with an
+//! expected answers sidecar like the examples have.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::examples;
+use crate::input::{Input, Source};
+use crate::runner;
+use crate::solution::Solver;
+use crate::verify::Verdict;
+
+/// The result of running one part against one file.
+pub struct Row {
+    /// The input file
+    pub path: PathBuf,
+    /// The part, 1 or 2
+    pub part: u8,
+    /// The answer, or what went wrong
+    pub answer: String,
+    /// How long the part took
+    pub elapsed: Duration,
+    /// How the answer compares with the sidecar's
+    pub verdict: Verdict,
+}
+
+impl Row {
+    /// Whether the part failed or didn't match the expected answer. The
+    /// sidecar is optional, so a file without one doesn't fail.
+    pub fn failed(&self) -> bool {
+        self.verdict.wrong()
+    }
+
+    fn result(&self) -> String {
+        match &self.verdict {
+            Verdict::Match => "pass".to_string(),
+            Verdict::Mismatch { expected, .. } => format!("FAIL, expected {}", expected.serialize()),
+            Verdict::Failed(_) => "FAIL".to_string(),
+            Verdict::Missing(_) | Verdict::Skipped(_) => "-".to_string(),
+        }
+    }
+}
+
+/// Expands the arguments into input files. Directories stand for the
+/// `.txt` files in them, and arguments with wildcards are glob patterns.
+pub fn files(args: &[String]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = Vec::new();
+    for arg in args {
+        let path = Path::new(arg);
+        let mut matched: Vec<PathBuf> = if path.is_dir() {
+            fs::read_dir(path)
+                .map_err(|e| format!("can't read directory {}: {}", path.display(), e))?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .filter(|p| is_input(p))
+                .collect()
+        } else if arg.contains(['*', '?', '[']) {
+            glob::glob(arg)
+                .map_err(|e| format!("bad pattern `{}`: {}", arg, e))?
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .filter(|p| is_input(p))
+                .collect()
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        if matched.is_empty() {
+            return Err(format!("no input files match {}", arg).into());
+        }
+        matched.sort();
+        files.extend(matched.into_iter().filter(|p| !files.contains(p)).collect::<Vec<_>>());
+    }
+
+    Ok(files)
+}
+
+/// Whether a file found in a directory or by a pattern is an input, as
+/// opposed to an answers sidecar or anything else lying around.
+fn is_input(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| ext == "txt")
+}
+
+/// Runs the parts against every file, judging the answers against the
+/// file's `.answers` sidecar if it has one.
+pub fn run(solver: &'static dyn Solver, parts: &[u8], files: &[PathBuf], budget: Option<Duration>) -> Result<Vec<Row>, Box<dyn Error>> {
+    let mut rows = Vec::new();
+    for path in files {
+        let file = examples::read(path.clone())?;
+        for &part in parts {
+            let input = Input { text: file.text.clone(), source: Source::File(path.clone()) };
+            let outcome = runner::quietly(|| runner::run_part(solver, part, Some(input), budget));
+            let answer = outcome.status.describe();
+            let verdict = Verdict::judge(outcome.status, file.expected.get(&part));
+            rows.push(Row { path: path.clone(), part, answer, elapsed: outcome.elapsed, verdict });
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Prints a row per file and part, with the total at the bottom.
+pub fn print_table(rows: &[Row]) {
+    let files: Vec<String> = rows.iter().map(|r| r.path.display().to_string()).collect();
+    let file_width = files.iter().map(|f| f.chars().count()).max().unwrap_or(0).max("File".len());
+    let answer_width = rows.iter().map(|r| r.answer.chars().count()).max().unwrap_or(0).max("Answer".len());
+
+    println!(
+        "{:<fw$}  {:>4}  {:<aw$}  {:>12}  Result",
+        "File", "Part", "Answer", "Time", fw = file_width, aw = answer_width,
+    );
+    for (row, file) in rows.iter().zip(files.iter()) {
+        println!(
+            "{:<fw$}  {:>4}  {:<aw$}  {:>12}  {}",
+            file, row.part, row.answer, format!("{:.2?}", row.elapsed), row.result(), fw = file_width, aw = answer_width,
+        );
+    }
+
+    let count = |f: fn(&Verdict) -> bool| rows.iter().filter(|r| f(&r.verdict)).count();
+    println!(
+        "{} passed, {} failed, {} without an expected answer",
+        count(|v| matches!(v, Verdict::Match)),
+        count(Verdict::wrong),
+        count(|v| matches!(v, Verdict::Missing(_) | Verdict::Skipped(_))),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files() {
+        let dir = std::env::temp_dir().join(format!("aoc2021-batch-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested.txt")).unwrap();
+        for name in ["b.txt", "a.txt", "a.answers", "notes.md"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        let arg = |s: &str| dir.join(s).to_string_lossy().into_owned();
+        let expected = vec![dir.join("a.txt"), dir.join("b.txt")];
+
+        assert_eq!(files(&[arg("")]).unwrap(), expected);
+        assert_eq!(files(&[arg("*")]).unwrap(), expected);
+        assert_eq!(files(&[arg("b.*"), arg("*.txt")]).unwrap(), [dir.join("b.txt"), dir.join("a.txt")]);
+        assert!(files(&[arg("*.md.txt")]).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed() {
+        let row = |verdict| Row { path: PathBuf::new(), part: 1, answer: String::new(), elapsed: Duration::ZERO, verdict };
+        assert!(!row(Verdict::Match).failed());
+        assert!(!row(Verdict::Missing(1.into())).failed());
+        assert!(row(Verdict::Mismatch { expected: 1.into(), actual: 2.into() }).failed());
+    }
+}
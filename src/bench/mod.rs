@@ -0,0 +1,178 @@
+//! Repeated, timed runs of a part.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolveError;
+use crate::input::Input;
+use crate::runner;
+use crate::solution::{Registry, Solver};
+
+/// Summary of a set of timings, in nanoseconds.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Stats {
+    /// Mean
+    pub mean_ns: f64,
+    /// Median
+    pub median_ns: f64,
+    /// Fastest
+    pub min_ns: f64,
+    /// Population standard deviation
+    pub stddev_ns: f64,
+}
+
+impl Stats {
+    /// Summarizes the samples. Panics if there are none.
+    pub fn new(samples: &[Duration]) -> Stats {
+        let mut ns: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        ns.sort_by(|a, b| a.total_cmp(b));
+
+        let n = ns.len() as f64;
+        let mean = ns.iter().sum::<f64>() / n;
+        let variance = ns.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        let median = if ns.len().is_multiple_of(2) {
+            (ns[ns.len() / 2 - 1] + ns[ns.len() / 2]) / 2.0
+        } else {
+            ns[ns.len() / 2]
+        };
+
+        Stats { mean_ns: mean, median_ns: median, min_ns: ns[0], stddev_ns: variance.sqrt() }
+    }
+}
+
+/// Parse and solve timings of one part.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Measurement {
+    /// The day
+    pub day: u8,
+    /// The part, 1 or 2
+    pub part: u8,
+    /// Time spent parsing
+    pub parse: Stats,
+    /// Time spent solving
+    pub solve: Stats,
+}
+
+/// The results of one `bench` invocation, as written to the JSON file.
+#[derive(Serialize, Deserialize)]
+pub struct Report {
+    /// Untimed runs before the timed ones
+    pub warmup: u32,
+    /// Timed runs of each part
+    pub runs: u32,
+    /// One per part
+    pub measurements: Vec<Measurement>,
+}
+
+impl Report {
+    /// Writes the report as pretty printed JSON.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| format!("can't write {}: {}", path.display(), e).into())
+    }
+}
+
+/// What benchmarking a set of days came to.
+pub struct Run {
+    /// The parts that could be benchmarked
+    pub measurements: Vec<Measurement>,
+    /// The day, part and error or panic message of each part that failed
+    pub failures: Vec<(u8, u8, String)>,
+}
+
+/// Benchmarks the solved `parts` of every registered day matching the
+/// filter, see [`measure`]. Days that `input` has no input for are left
+/// out, and an error from it ends the run.
+pub fn run_all<F, I>(
+    registry: &'static Registry, filter: F, mut input: I, parts: &[u8], warmup: u32, runs: u32, budget: Option<Duration>,
+) -> Result<Run, Box<dyn Error>>
+where
+    F: Fn(u8) -> bool,
+    I: FnMut(u8) -> Result<Option<Input>, Box<dyn Error>>,
+{
+    let mut run = Run { measurements: Vec::new(), failures: Vec::new() };
+    for solver in registry.iter().filter(|s| filter(s.metadata().day)) {
+        let metadata = solver.metadata();
+        let input = match input(metadata.day)? {
+            Some(input) => input,
+            None => continue,
+        };
+
+        for &part in parts.iter().filter(|part| !metadata.unsolved.contains(part)) {
+            match measure(solver, part, &input.text, warmup, runs, budget) {
+                Ok(measurement) => run.measurements.push(measurement),
+                Err(message) => run.failures.push((metadata.day, part, message)),
+            }
+        }
+    }
+
+    Ok(run)
+}
+
+/// Runs a part `warmup` times untimed, then `runs` times timed, on a
+/// worker thread allowed the time budget for each run. Returns the error
+/// or panic message if the solver fails.
+pub fn measure(
+    solver: &'static dyn Solver, part: u8, input: &str, warmup: u32, runs: u32, budget: Option<Duration>,
+) -> Result<Measurement, String> {
+    let input = input.to_string();
+    let budget = budget.map(|b| b * (warmup + runs));
+    let result = runner::quietly(|| runner::isolate(budget, move || -> Result<_, SolveError> {
+        for _ in 0..warmup {
+            solver.solve_timed(part, &input)?;
+        }
+
+        let timings = (0..runs)
+            .map(|_| solver.solve_timed(part, &input))
+            .collect::<Result<Vec<_>, _>>()?;
+        let parse: Vec<_> = timings.iter().map(|t| t.parse).collect();
+        let solve: Vec<_> = timings.iter().map(|t| t.solve).collect();
+
+        Ok(Measurement { day: solver.metadata().day, part, parse: Stats::new(&parse), solve: Stats::new(&solve) })
+    }));
+
+    match result {
+        Ok(Ok(measurement)) => Ok(measurement),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(interrupted) => Err(interrupted.to_string()),
+    }
+}
+
+/// Prints a row of timings per part.
+pub fn print_table(measurements: &[Measurement]) {
+    println!(
+        "{:>3}  {:>4}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+        "Day", "Part", "Parse mean", "Solve mean", "Solve median", "Solve min", "Solve stddev",
+    );
+    for m in measurements {
+        println!(
+            "{:>3}  {:>4}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+            m.day, m.part, format_ns(m.parse.mean_ns), format_ns(m.solve.mean_ns),
+            format_ns(m.solve.median_ns), format_ns(m.solve.min_ns), format_ns(m.solve.stddev_ns),
+        );
+    }
+}
+
+/// Formats a time in nanoseconds with a unit to suit it.
+pub fn format_ns(ns: f64) -> String {
+    format!("{:.2?}", Duration::from_nanos(ns as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<_> = [4, 1, 3, 2, 5, 3].iter().map(|&ms| Duration::from_millis(ms)).collect();
+        let stats = Stats::new(&samples);
+        assert_eq!(stats.mean_ns, 3e6);
+        assert_eq!(stats.median_ns, 3e6);
+        assert_eq!(stats.min_ns, 1e6);
+        assert!((stats.stddev_ns - (10.0_f64 / 6.0).sqrt() * 1e6).abs() < 1.0);
+    }
+}
@@ -0,0 +1,279 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use aoc2021_rs::input::STDIN;
+
+/// Advent of Code 2021 solutions
+#[derive(Parser)]
+#[command(name = "aoc2021-rs", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Directory holding local puzzle inputs named dayNN.txt
+    #[arg(long, global = true, env = "AOC_INPUT_DIR", default_value = "inputs")]
+    pub input_dir: PathBuf,
+
+    /// Seconds each part may run before it's reported as timed out, 0 for
+    /// no limit
+    #[arg(long, global = true, env = "AOC_TIMEOUT", default_value_t = 60.0)]
+    pub timeout: f64,
+
+    /// Log more of what the solvers are doing, repeat for more detail
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Log levels, like `debug` or `info,day22=trace`, overriding -v
+    #[arg(long, global = true, env = "AOC_LOG")]
+    pub log: Option<String>,
+
+    /// Count the allocations each part makes and report them with the
+    /// timings
+    #[arg(long, global = true)]
+    pub allocs: bool,
+}
+
+impl Cli {
+    /// The time budget of each part, if there is one.
+    pub fn budget(&self) -> Option<Duration> {
+        (self.timeout > 0.0).then(|| Duration::from_secs_f64(self.timeout))
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run a day against its puzzle input
+    Run(Selection),
+    /// Run a day against its examples, or against the given example files
+    Test(TestArgs),
+    /// Run every day and part against its puzzle input and print a summary table
+    All(AllArgs),
+    /// Time repeated runs of one or every day against its puzzle input
+    Bench(BenchArgs),
+    /// Check every solver against the known answers file
+    Verify(VerifyArgs),
+    /// List the registered days
+    List,
+    /// Create a new day from the template and register it
+    New(NewArgs),
+    /// Re-run a day against its input and examples whenever they change
+    Watch(WatchArgs),
+    /// Run a day against many input files, checking each against its
+    /// `.answers` sidecar if it has one
+    Batch(BatchArgs),
+}
+
+#[derive(Args)]
+pub struct Selection {
+    /// Day to run (1-25)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Part to run (1 or 2), both parts if omitted
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Read the puzzle input from this file instead of the input directory,
+    /// or from standard input if the file is `-`
+    #[arg(short, long)]
+    pub input: Option<PathBuf>,
+
+    /// Read the puzzle input from standard input, same as `--input -`
+    #[arg(long, conflicts_with = "input")]
+    pub stdin: bool,
+
+    /// Output format, json and csv print only the results to stdout
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    /// Human readable lines and tables
+    Text,
+    /// An array with one record per part
+    Json,
+    /// A header line, then one row per part
+    Csv,
+}
+
+#[derive(Args)]
+pub struct AllArgs {
+    /// Output format, json and csv print only the results to stdout
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+impl Selection {
+    /// The input file given on the command line, if any.
+    pub fn input(&self) -> Option<&Path> {
+        if self.stdin {
+            Some(Path::new(STDIN))
+        } else {
+            self.input.as_deref()
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct TestArgs {
+    /// Day to test (1-25)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Part to test (1 or 2), both parts if omitted
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Example files to feed to the solver through aoc-helper, instead of
+    /// the day's examples with their expected answers
+    #[arg(short, long = "input")]
+    pub inputs: Vec<PathBuf>,
+
+    /// Directory holding the examples, in one dayNN directory per day
+    #[arg(long, env = "AOC_EXAMPLES_DIR", default_value = "examples")]
+    pub examples_dir: PathBuf,
+}
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct BenchArgs {
+    #[command(subcommand)]
+    pub command: Option<BenchCommand>,
+
+    #[command(flatten)]
+    pub run: BenchRunArgs,
+}
+
+const BENCH_HISTORY: &str = ".bench/history.jsonl";
+
+#[derive(Subcommand)]
+pub enum BenchCommand {
+    /// Compare the latest results at two commits and flag parts that got slower
+    Compare(CompareArgs),
+}
+
+#[derive(Args)]
+pub struct BenchRunArgs {
+    /// Day to benchmark (1-25), every day if omitted
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: Option<u8>,
+
+    /// Part to benchmark (1 or 2), both parts if omitted
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Read the puzzle input from this file instead of the input directory,
+    /// or from standard input if the file is `-`
+    #[arg(short, long, requires = "day")]
+    pub input: Option<PathBuf>,
+
+    /// Number of untimed runs per part before timing
+    #[arg(short, long, default_value_t = 3)]
+    pub warmup: u32,
+
+    /// Number of timed runs per part
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub runs: u32,
+
+    /// Write the results to this file as JSON
+    #[arg(long)]
+    pub json: Option<PathBuf>,
+
+    /// File the results of every run are appended to
+    #[arg(long, default_value = BENCH_HISTORY)]
+    pub history: PathBuf,
+
+    /// Don't append the results to the history
+    #[arg(long)]
+    pub no_history: bool,
+}
+
+#[derive(Args)]
+pub struct CompareArgs {
+    /// Commit to compare against, the previous benchmarked commit if omitted
+    #[arg(short, long)]
+    pub baseline: Option<String>,
+
+    /// Commit to check, HEAD if omitted
+    #[arg(short, long)]
+    pub commit: Option<String>,
+
+    /// How much slower, in percent, a part may get before it's flagged
+    #[arg(short, long, default_value_t = 10.0)]
+    pub threshold: f64,
+
+    /// File holding the results of earlier runs
+    #[arg(long, default_value = BENCH_HISTORY)]
+    pub history: PathBuf,
+}
+
+#[derive(Args)]
+pub struct VerifyArgs {
+    /// Only verify this day (1-25)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: Option<u8>,
+
+    /// File holding the known answers, one `day part answer` line per part
+    #[arg(long, default_value = "answers.txt")]
+    pub answers: PathBuf,
+
+    /// Add the answers of parts that have no known answer yet to the file
+    #[arg(long)]
+    pub record: bool,
+}
+
+#[derive(Args)]
+pub struct NewArgs {
+    /// Day to create (1-25)
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Title of the puzzle
+    #[arg(short, long, default_value = "")]
+    pub title: String,
+
+    /// Directory holding the examples, in one dayNN directory per day
+    #[arg(long, env = "AOC_EXAMPLES_DIR", default_value = "examples")]
+    pub examples_dir: PathBuf,
+}
+
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Day to watch (1-25)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Part to run (1 or 2), both parts if omitted
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Watch this puzzle input instead of the one in the input directory
+    #[arg(short, long)]
+    pub input: Option<PathBuf>,
+
+    /// Directory holding the examples, in one dayNN directory per day
+    #[arg(long, env = "AOC_EXAMPLES_DIR", default_value = "examples")]
+    pub examples_dir: PathBuf,
+
+    /// How often to check the files for changes, in milliseconds
+    #[arg(long, default_value_t = 500)]
+    pub interval: u64,
+}
+
+#[derive(Args)]
+pub struct BatchArgs {
+    /// Day to run (1-25)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Part to run (1 or 2), both parts if omitted
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Input files, directories of `.txt` inputs, or glob patterns
+    #[arg(required = true)]
+    pub inputs: Vec<String>,
+}
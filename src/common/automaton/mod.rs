@@ -0,0 +1,162 @@
+//! Running states that evolve one step at a time, like cellular automata.
+//!
+//! ```
+//! use aoc2021_rs::common::automaton::{Automaton, Engine};
+//!
+//! // Halves until it reaches zero.
+//! struct Halving(u32);
+//!
+//! impl Automaton for Halving {
+//!     fn step(&mut self) -> bool {
+//!         let before = self.0;
+//!         self.0 /= 2;
+//!         self.0 != before
+//!     }
+//! }
+//!
+//! let mut engine = Engine::new(Halving(100));
+//! assert_eq!(engine.run_until_stable(), 8);
+//! assert_eq!(engine.state().0, 0);
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A state that knows how to advance itself by one step.
+pub trait Automaton {
+    /// Advances one step, returning whether anything changed.
+    fn step(&mut self) -> bool;
+}
+
+type Callback<'a, A> = dyn FnMut(usize, &A) + 'a;
+
+/// Where a run of states starts repeating itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The step at which the first state of the cycle was seen.
+    pub start: usize,
+    /// How many steps it takes to come back round to it.
+    pub period: usize,
+}
+
+/// Drives an automaton, counting its steps and handing each new state to
+/// an optional callback, for rendering or logging.
+pub struct Engine<'a, A> {
+    state: A,
+    steps: usize,
+    on_step: Option<Box<Callback<'a, A>>>,
+}
+
+impl<'a, A: Automaton> Engine<'a, A> {
+    /// An engine starting from `state`, with no steps run.
+    pub fn new(state: A) -> Engine<'a, A> {
+        Engine { state, steps: 0, on_step: None }
+    }
+
+    /// Calls `f` with the step number and the state after every step.
+    pub fn on_step(mut self, f: impl FnMut(usize, &A) + 'a) -> Engine<'a, A> {
+        self.on_step = Some(Box::new(f));
+        self
+    }
+
+    /// The current state.
+    pub fn state(&self) -> &A {
+        &self.state
+    }
+
+    /// Gives up the engine for its current state.
+    pub fn into_state(self) -> A {
+        self.state
+    }
+
+    /// How many steps have been run so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Runs one step, returning whether anything changed.
+    pub fn step(&mut self) -> bool {
+        let changed = self.state.step();
+        self.steps += 1;
+        if let Some(f) = self.on_step.as_mut() {
+            f(self.steps, &self.state);
+        }
+
+        changed
+    }
+
+    /// Runs `n` more steps.
+    pub fn run(&mut self, n: usize) -> &A {
+        for _ in 0..n {
+            self.step();
+        }
+
+        &self.state
+    }
+
+    /// Runs until a step leaves `done` true of the state, returning the
+    /// number of steps run in all.
+    pub fn run_until(&mut self, mut done: impl FnMut(&A) -> bool) -> usize {
+        loop {
+            self.step();
+            if done(&self.state) {
+                return self.steps;
+            }
+        }
+    }
+
+    /// Runs until a step changes nothing, returning the number of steps
+    /// run in all, that last one included.
+    pub fn run_until_stable(&mut self) -> usize {
+        while self.step() {}
+        self.steps
+    }
+
+    /// Runs until a state comes round again, keeping every state seen on
+    /// the way to recognise it.
+    pub fn find_cycle(&mut self) -> Cycle
+    where
+        A: Clone + Eq + Hash,
+    {
+        let mut seen = HashMap::new();
+        loop {
+            if let Some(&start) = seen.get(&self.state) {
+                return Cycle { start, period: self.steps - start };
+            }
+
+            seen.insert(self.state.clone(), self.steps);
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts up by three, wrapping at ten, after a run-up from a start
+    /// outside of that range.
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    struct Counter(u32);
+
+    impl Automaton for Counter {
+        fn step(&mut self) -> bool {
+            self.0 = if self.0 >= 10 { self.0 - 10 } else { (self.0 + 3) % 10 };
+            true
+        }
+    }
+
+    #[test]
+    fn test_engine() {
+        let mut states = Vec::new();
+        let mut engine = Engine::new(Counter(25)).on_step(|step, state: &Counter| states.push((step, state.0)));
+
+        assert_eq!(engine.run(2).0, 5);
+        assert_eq!(engine.run_until(|state| state.0 == 1), 4);
+        assert_eq!(engine.find_cycle(), Cycle { start: 4, period: 10 });
+        assert_eq!(engine.steps(), 14);
+
+        drop(engine);
+        assert_eq!(states[..5], [(1, 15), (2, 5), (3, 8), (4, 1), (5, 4)]);
+    }
+}
@@ -0,0 +1,226 @@
+//! A dense two dimensional grid.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::error::SolveError;
+
+/// A position as `(x, y)`, with `(0, 0)` the top left corner. Signed, so
+/// that neighbours of edge cells can be computed and then looked up.
+pub type Pos = (i64, i64);
+
+const NEIGHBOURS4: [Pos; 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const NEIGHBOURS8: [Pos; 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// Cells stored row by row. Indexing with a position outside the grid
+/// panics, [`Grid::get`] returns `None` instead and
+/// [`Grid::get_wrapping`] wraps around the edges.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid from its cells, row by row. Panics if there aren't
+    /// `width * height` of them.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "a {}x{} grid needs {} cells", width, height, width * height);
+        Grid { width, height, cells }
+    }
+
+    /// A grid with each cell computed from its position.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Grid<T> {
+        let cells = (0..height as i64)
+            .flat_map(|y| (0..width as i64).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+        Grid { width, height, cells }
+    }
+
+    /// Parses a map with one character per cell, one row per line.
+    /// `expected` describes the characters `cell` accepts, for the error
+    /// on any other character.
+    pub fn parse(input: &str, expected: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, SolveError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for line in input.lines() {
+            let mut count = 0;
+            for (i, c) in line.char_indices() {
+                let value = cell(c)
+                    .ok_or_else(|| SolveError::at(input, &line[i..], format!("expected {}, got `{}`", expected, c)))?;
+                cells.push(value);
+                count += 1;
+            }
+
+            match width {
+                None => width = Some(count),
+                Some(width) if width != count => return Err(SolveError::at(
+                    input, line, format!("expected {} cells on every line, this one has {}", width, count),
+                )),
+                _ => (),
+            }
+            height += 1;
+        }
+
+        if cells.is_empty() {
+            return Err(SolveError::at(input, input, format!("expected a grid of {}, got no cells", expected)));
+        }
+
+        Ok(Grid { width: width.unwrap_or(0), height, cells })
+    }
+
+    /// The number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the position is inside the grid.
+    pub fn contains(&self, pos: Pos) -> bool {
+        let (x, y) = pos;
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// The cell at a position, `None` outside the grid.
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    /// Like [`Grid::get`], but mutable.
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// The position inside the grid that `pos` ends up at when wrapping
+    /// around the edges. Panics if the grid is empty.
+    pub fn wrap(&self, pos: Pos) -> Pos {
+        assert!(!self.cells.is_empty(), "can't wrap around an empty grid");
+        let (x, y) = pos;
+        (x.rem_euclid(self.width as i64), y.rem_euclid(self.height as i64))
+    }
+
+    /// The cell at the wrapped position, see [`Grid::wrap`].
+    pub fn get_wrapping(&self, pos: Pos) -> &T {
+        &self[self.wrap(pos)]
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width as i64;
+        (0..self.height as i64).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Every cell, row by row.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Each row as a slice, from the top.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The cells of each column, from the left.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    /// The orthogonal neighbours of a position that are inside the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offsets(pos, &NEIGHBOURS4)
+    }
+
+    /// The orthogonal and diagonal neighbours of a position that are
+    /// inside the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offsets(pos, &NEIGHBOURS8)
+    }
+
+    /// A grid of the same size with `f` applied to each cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    fn offsets<'a>(&'a self, pos: Pos, offsets: &'static [Pos]) -> impl Iterator<Item = Pos> + 'a {
+        let (x, y) = pos;
+        offsets.iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(|&n| self.contains(n))
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        self.contains(pos).then(|| pos.1 as usize * self.width + pos.0 as usize)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos).unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, width, height))
+    }
+}
+
+/// Draws the grid one row per line, with the cells next to each other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let grid = Grid::parse("123\n456", "a digit", |c| c.to_digit(10)).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(*grid.get_wrapping((-1, 2)), 3);
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+        assert_eq!(grid.columns().map(|c| c.sum::<u32>()).collect::<Vec<_>>(), vec![5, 7, 9]);
+        assert_eq!(grid.to_string(), "123\n456");
+
+        let error = Grid::parse("12\n3x", "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(error.summary(), "line 2, column 2: expected a digit, got `x`");
+        assert!(Grid::parse("12\n345", "a digit", |c| c.to_digit(10)).is_err());
+        assert!(Grid::parse("", "a digit", |c| c.to_digit(10)).is_err());
+        assert!(Grid::parse("\n\n", "a digit", |c| c.to_digit(10)).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "can't wrap around an empty grid")]
+    fn test_wrap_empty() {
+        Grid::<u32>::new(0, 0, Vec::new()).wrap((1, 1));
+    }
+}
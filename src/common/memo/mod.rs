@@ -0,0 +1,107 @@
+//! Memoized recursion, for solutions written as a recursive definition
+//! over some hashable state.
+//!
+//! ```
+//! use aoc2021_rs::common::memo;
+//!
+//! let fib = memo::recursive(90u64, |memo, n| if n < 2 { n } else { memo.get(n - 1) + memo.get(n - 2) });
+//! assert_eq!(fib, 2880067194370816120);
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::panic;
+use std::thread;
+
+/// How many nested calls run on one stack before the recursion moves on
+/// to a fresh thread.
+const CALLS_PER_STACK: usize = 1024;
+const STACK_SIZE: usize = 16 * 1024 * 1024;
+
+type Definition<'f, K, V> = dyn Fn(&mut Memo<'f, K, V>, K) -> V + Sync + 'f;
+
+/// A cache of the values of a recursive definition, filled in as they're
+/// asked for.
+pub struct Memo<'f, K, V> {
+    cache: HashMap<K, V>,
+    definition: &'f Definition<'f, K, V>,
+    depth: usize,
+}
+
+impl<'f, K, V> Memo<'f, K, V>
+where
+    K: Clone + Eq + Hash + Send,
+    V: Clone + Send,
+{
+    /// An empty cache for `definition`, which computes the value for a key
+    /// and gets the values it depends on through the memo it's handed.
+    pub fn new(definition: &'f Definition<'f, K, V>) -> Memo<'f, K, V> {
+        Memo { cache: HashMap::new(), definition, depth: 0 }
+    }
+
+    /// The value for `key`, computing it unless it's already cached.
+    ///
+    /// However deep the recursion goes it won't overflow the stack: every
+    /// so often it carries on in a new thread, with a stack of its own.
+    pub fn get(&mut self, key: K) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+
+        self.depth += 1;
+        let definition = self.definition;
+        let value = if self.depth.is_multiple_of(CALLS_PER_STACK) {
+            let (memo, key) = (&mut *self, key.clone());
+            thread::scope(|scope| {
+                thread::Builder::new()
+                    .stack_size(STACK_SIZE)
+                    .spawn_scoped(scope, move || definition(memo, key))
+                    .expect("failed to spawn a thread to continue the recursion")
+                    .join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+        } else {
+            definition(self, key.clone())
+        };
+        self.depth -= 1;
+
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// How many values have been computed so far.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Whether nothing has been computed yet.
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+/// Evaluates a recursive `definition` at `key` with a fresh cache.
+pub fn recursive<K, V>(key: K, definition: impl Fn(&mut Memo<K, V>, K) -> V + Sync) -> V
+where
+    K: Clone + Eq + Hash + Send,
+    V: Clone + Send,
+{
+    Memo::new(&definition).get(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo() {
+        // Deep enough to overflow a test thread's stack if it all ran on one.
+        let sum = recursive(200_000u64, |memo, n| if n == 0 { 0 } else { n + memo.get(n - 1) });
+        assert_eq!(sum, 200_000 * 200_001 / 2);
+
+        let definition = |memo: &mut Memo<u64, u64>, n: u64| if n < 2 { n } else { memo.get(n - 1) + memo.get(n - 2) };
+        let mut memo = Memo::new(&definition);
+        assert_eq!(memo.get(30), 832040);
+        assert_eq!(memo.len(), 31);
+    }
+}
@@ -1,17 +1,62 @@
+//! Parsing helpers shared by the days.
+
+use std::str::FromStr;
+
 use itertools::Itertools;
 
-pub fn read_integers(input: &str) -> Option<Vec<i128>> {
-    input.split_whitespace()
-        .map(|line| line.parse().ok())
+use crate::error::SolveError;
+
+pub mod automaton;
+pub mod grid;
+pub mod memo;
+pub mod scan;
+pub mod search;
+
+pub use grid::Grid;
+pub use scan::FromInput;
+
+/// Every whitespace separated integer in the input.
+pub fn read_integers(input: &str) -> Result<Vec<i128>, SolveError> {
+    scan::words(input, input)
+}
+
+/// Whitespace separated label and integer pairs, like `forward 5`.
+pub fn read_labeled_integers(input: &str) -> Result<Vec<(&str, i128)>, SolveError> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    if words.len() % 2 == 1 {
+        return Err(SolveError::at(input, words[words.len() - 1], "expected a value after the label"));
+    }
+
+    words.into_iter()
+        .tuples::<(&str, &str)>()
+        .map(|(a, b)| Ok((a, parse(input, b)?)))
         .collect()
 }
 
-pub fn read_labeled_integers(input: &str) -> Option<Vec<(&str, i128)>> {
+/// Parses `fragment`, a slice of `input`, reporting where it is in the
+/// input if it isn't valid.
+pub fn parse<T: FromStr>(input: &str, fragment: &str) -> Result<T, SolveError> {
+    fragment.parse()
+        .map_err(|_| SolveError::at(input, fragment, format!("can't parse `{}` as {}", fragment, type_name::<T>())))
+}
+
+/// Reads a grid of single digits, one row per line.
+pub fn read_digits(input: &str) -> Result<Vec<Vec<u32>>, SolveError> {
     input.split_whitespace()
-        .tuples::<(&str, &str)>()
-        .map(|(a, b)| match b.parse() {
-            Ok(b) => Some((a, b)),
-            _ => None,
-        })
+        .map(|line|
+            line.char_indices()
+                .map(|(i, c)| c.to_digit(10)
+                    .ok_or_else(|| SolveError::at(input, &line[i..], format!("expected a digit, got `{}`", c))))
+                .collect()
+        )
         .collect()
 }
+
+fn type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    match name {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => "an integer",
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => "a non-negative integer",
+        _ => name.rsplit("::").next().unwrap_or(name),
+    }
+}
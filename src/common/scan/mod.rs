@@ -0,0 +1,166 @@
+//! Parsing structured input into typed values, reporting where in the
+//! input anything doesn't fit.
+//!
+//! Every function takes the whole `input` along with the `fragment` of it
+//! being parsed, so that errors can point at the line and column.
+//!
+//! ```
+//! use aoc2021_rs::common::scan;
+//!
+//! let input = "0,9 -> 5,9\n8,0 -> 0,8";
+//! let lines: Vec<(i64, i64, i64, i64)> = scan::lines_matching(input, input, "{},{} -> {},{}").unwrap();
+//! assert_eq!(lines[1], (8, 0, 0, 8));
+//! ```
+
+use std::str::FromStr;
+
+use crate::common;
+use crate::error::SolveError;
+
+/// A value parsed from a fragment of the input.
+pub trait FromInput: Sized {
+    /// Parses `fragment`, a slice of `input`, locating errors in `input`.
+    fn from_input(input: &str, fragment: &str) -> Result<Self, SolveError>;
+}
+
+impl<T: FromStr> FromInput for T {
+    fn from_input(input: &str, fragment: &str) -> Result<T, SolveError> {
+        common::parse(input, fragment)
+    }
+}
+
+/// A tuple of values parsed from the fields of a pattern.
+pub trait FromFields: Sized {
+    /// How many fields the tuple takes.
+    const COUNT: usize;
+    /// Parses the fields, which are slices of `input`, in order.
+    fn from_fields(input: &str, fields: &[&str]) -> Result<Self, SolveError>;
+}
+
+macro_rules! from_fields {
+    ($($t:ident),+) => {
+        impl<$($t: FromInput),+> FromFields for ($($t,)+) {
+            const COUNT: usize = [$(stringify!($t)),+].len();
+
+            fn from_fields(input: &str, fields: &[&str]) -> Result<Self, SolveError> {
+                let mut fields = fields.iter();
+                Ok(($($t::from_input(input, fields.next().unwrap())?,)+))
+            }
+        }
+    };
+}
+
+from_fields!(A);
+from_fields!(A, B);
+from_fields!(A, B, C);
+from_fields!(A, B, C, D);
+from_fields!(A, B, C, D, E);
+from_fields!(A, B, C, D, E, F);
+from_fields!(A, B, C, D, E, F, G);
+from_fields!(A, B, C, D, E, F, G, H);
+
+/// Matches the fragment against a pattern of literal text with `{}`
+/// placeholders, returning what each placeholder matched. A placeholder
+/// matches everything up to the first occurrence of the literal after it,
+/// or the rest of the fragment if it ends the pattern.
+pub fn fields<'a>(input: &str, fragment: &'a str, pattern: &str) -> Result<Vec<&'a str>, SolveError> {
+    let literals: Vec<&str> = pattern.split("{}").collect();
+    assert!(
+        literals.get(1..literals.len() - 1).unwrap_or_default().iter().all(|l| !l.is_empty()),
+        "placeholders in `{}` must be separated by literal text", pattern,
+    );
+
+    let rest = fragment.strip_prefix(literals[0])
+        .ok_or_else(|| SolveError::at(input, fragment, format!("expected `{}`", pattern)))?;
+
+    let mut fields = Vec::new();
+    let mut rest = rest;
+    for literal in &literals[1..] {
+        if literal.is_empty() {
+            fields.push(rest);
+            rest = &rest[rest.len()..];
+            continue;
+        }
+
+        let end = rest.find(literal)
+            .ok_or_else(|| SolveError::at(input, rest, format!("expected `{}`", literal)))?;
+        fields.push(&rest[..end]);
+        rest = &rest[end + literal.len()..];
+    }
+
+    if !rest.is_empty() {
+        return Err(SolveError::at(input, rest, format!("unexpected `{}` after `{}`", rest, pattern)));
+    }
+
+    Ok(fields)
+}
+
+/// Matches the fragment against a pattern, see [`fields`], and parses
+/// the fields into a tuple.
+pub fn scan<T: FromFields>(input: &str, fragment: &str, pattern: &str) -> Result<T, SolveError> {
+    let fields = fields(input, fragment, pattern)?;
+    assert_eq!(fields.len(), T::COUNT, "`{}` has {} placeholders", pattern, fields.len());
+    T::from_fields(input, &fields)
+}
+
+/// Parses each line of the fragment.
+pub fn lines<T: FromInput>(input: &str, fragment: &str) -> Result<Vec<T>, SolveError> {
+    fragment.lines().map(|line| T::from_input(input, line)).collect()
+}
+
+/// Matches each line of the fragment against a pattern, see [`scan`].
+pub fn lines_matching<T: FromFields>(input: &str, fragment: &str, pattern: &str) -> Result<Vec<T>, SolveError> {
+    fragment.lines().map(|line| scan(input, line, pattern)).collect()
+}
+
+/// Parses each whitespace separated word of the fragment.
+pub fn words<T: FromInput>(input: &str, fragment: &str) -> Result<Vec<T>, SolveError> {
+    fragment.split_whitespace().map(|word| T::from_input(input, word)).collect()
+}
+
+/// Parses each item between separators, ignoring whitespace around them.
+pub fn separated<T: FromInput>(input: &str, fragment: &str, separator: &str) -> Result<Vec<T>, SolveError> {
+    fragment.split(separator).map(|item| T::from_input(input, item.trim())).collect()
+}
+
+/// Splits the fragment into exactly `count` sections separated by blank
+/// lines.
+pub fn sections<'a>(input: &str, fragment: &'a str, count: usize) -> Result<Vec<&'a str>, SolveError> {
+    let sections: Vec<&str> = fragment.split("\n\n").collect();
+    if sections.len() != count {
+        let at = if sections.len() > count { sections[count] } else { &fragment[fragment.len()..] };
+        return Err(SolveError::at(input, at, format!(
+            "expected {} sections separated by blank lines, found {}", count, sections.len(),
+        )));
+    }
+
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        let input = "fold along x=5\nfold along y=-3";
+        let folds: Vec<(char, i64)> = lines_matching(input, input, "fold along {}={}").unwrap();
+        assert_eq!(folds, vec![('x', 5), ('y', -3)]);
+
+        let input = "fold along y-3";
+        let error = scan::<(char, i64)>(input, input, "fold along {}={}").unwrap_err();
+        assert_eq!(error.summary(), "line 1, column 12: expected `=`");
+        let input = "fold along x=5!";
+        let error = scan::<(char, i64)>(input, input, "fold along {}={}").unwrap_err();
+        assert_eq!(error.summary(), "line 1, column 14: can't parse `5!` as an integer");
+
+        let input = "1,2\n\n3 4\n\nextra";
+        let error = sections(input, input, 2).unwrap_err();
+        assert_eq!(error.summary(), "line 5, column 1: expected 2 sections separated by blank lines, found 3");
+        assert_eq!(separated::<u8>(input, "1, 2,3", ",").unwrap(), vec![1, 2, 3]);
+
+        assert!(fields("start", "start", "start").unwrap().is_empty());
+        assert_eq!(fields("stop", "stop", "start").unwrap_err().summary(), "line 1, column 1: expected `start`");
+        assert!(fields("start!", "start!", "start").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Shortest paths through a graph given by a neighbour function.
+//!
+//! States only need to be hashable, and are discovered lazily, so the
+//! same functions search grids and puzzle positions alike.
+//!
+//! ```
+//! use aoc2021_rs::common::search;
+//!
+//! // Reach 10 from 1 by doubling (cost 1) or adding one (cost 3).
+//! let found = search::dijkstra(1, |&n| vec![(n * 2, 1), (n + 1, 3)], |&n| n == 10);
+//! let path = found.path.unwrap();
+//! assert_eq!(path.cost, 6);
+//! assert_eq!(path.states, vec![1, 2, 4, 5, 10]);
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The cheapest way found from the start to a goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<S, C> {
+    /// The total cost of the steps.
+    pub cost: C,
+    /// Every state along the way, from the start to the goal inclusive.
+    pub states: Vec<S>,
+}
+
+/// How much work a search did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// States taken off the frontier and expanded.
+    pub expanded: usize,
+    /// Distinct states seen, expanded or not.
+    pub discovered: usize,
+    /// The largest the frontier grew.
+    pub peak_frontier: usize,
+}
+
+/// The outcome of a search: the path, unless no goal was reachable, and
+/// the work it took either way.
+#[derive(Clone, Debug)]
+pub struct Search<S, C> {
+    /// The path found, `None` if no goal was reachable.
+    pub path: Option<Path<S, C>>,
+    /// The work the search did.
+    pub stats: Stats,
+}
+
+/// The cost of a step or a path. `Default` is taken as zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<C: Copy + Ord + Add<Output = C> + Default> Cost for C {}
+
+/// A seen state, with the best cost found to it so far and the index of
+/// the state it was reached from.
+struct Node<S, C> {
+    state: S,
+    cost: C,
+    parent: Option<usize>,
+}
+
+/// A* search from `start` to the first state satisfying `is_goal`.
+///
+/// The `heuristic` must never overestimate the remaining cost, or the
+/// path found may not be the cheapest.
+pub fn astar<S, C, N, I, H, G>(start: S, mut neighbours: N, mut heuristic: H, mut is_goal: G) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut stats = Stats::default();
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((heuristic(&start), C::default(), 0)));
+    let mut index = HashMap::new();
+    index.insert(start.clone(), 0);
+    let mut nodes = vec![Node { state: start, cost: C::default(), parent: None }];
+
+    while let Some(Reverse((_, g, i))) = open.pop() {
+        // A cheaper way here was found after this entry was queued.
+        if g > nodes[i].cost {
+            continue;
+        }
+
+        stats.expanded += 1;
+        if is_goal(&nodes[i].state) {
+            stats.discovered = nodes.len();
+            return Search { path: Some(reconstruct(&nodes, i)), stats };
+        }
+
+        let next: Vec<(S, C)> = neighbours(&nodes[i].state).into_iter().collect();
+        for (state, step) in next {
+            let cost = g + step;
+            let j = match index.get(&state) {
+                Some(&j) if nodes[j].cost <= cost => continue,
+                Some(&j) => {
+                    nodes[j].cost = cost;
+                    nodes[j].parent = Some(i);
+                    j
+                }
+                None => {
+                    index.insert(state.clone(), nodes.len());
+                    nodes.push(Node { state, cost, parent: Some(i) });
+                    nodes.len() - 1
+                }
+            };
+
+            open.push(Reverse((cost + heuristic(&nodes[j].state), cost, j)));
+        }
+        stats.peak_frontier = stats.peak_frontier.max(open.len());
+    }
+
+    stats.discovered = nodes.len();
+    Search { path: None, stats }
+}
+
+/// Dijkstra's algorithm: A* without a heuristic.
+pub fn dijkstra<S, C, N, I, G>(start: S, neighbours: N, is_goal: G) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// Breadth-first search, for graphs where every step costs one. The cost
+/// of the path is its number of steps.
+pub fn bfs<S, N, I, G>(start: S, mut neighbours: N, mut is_goal: G) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut stats = Stats::default();
+    let mut open = VecDeque::from([0]);
+    let mut index = HashMap::new();
+    index.insert(start.clone(), 0);
+    let mut nodes = vec![Node { state: start, cost: 0, parent: None }];
+
+    while let Some(i) = open.pop_front() {
+        stats.expanded += 1;
+        if is_goal(&nodes[i].state) {
+            stats.discovered = nodes.len();
+            return Search { path: Some(reconstruct(&nodes, i)), stats };
+        }
+
+        let next: Vec<S> = neighbours(&nodes[i].state).into_iter().collect();
+        for state in next {
+            if index.contains_key(&state) {
+                continue;
+            }
+
+            index.insert(state.clone(), nodes.len());
+            open.push_back(nodes.len());
+            nodes.push(Node { state, cost: nodes[i].cost + 1, parent: Some(i) });
+        }
+        stats.peak_frontier = stats.peak_frontier.max(open.len());
+    }
+
+    stats.discovered = nodes.len();
+    Search { path: None, stats }
+}
+
+fn reconstruct<S: Clone, C: Copy>(nodes: &[Node<S, C>], goal: usize) -> Path<S, C> {
+    let mut states = Vec::new();
+    let mut current = Some(goal);
+    while let Some(i) = current {
+        states.push(nodes[i].state.clone());
+        current = nodes[i].parent;
+    }
+    states.reverse();
+
+    Path { cost: nodes[goal].cost, states }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search() {
+        // A 5x5 open square with a wall at x == 2 except for its bottom cell.
+        let neighbours = |&(x, y): &(i32, i32)| {
+            [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)].into_iter()
+                .filter(|&(x, y)| (0..5).contains(&x) && (0..5).contains(&y) && (x != 2 || y == 4))
+        };
+        let goal = |&p: &(i32, i32)| p == (4, 0);
+
+        let found = bfs((0, 0), neighbours, goal);
+        let path = found.path.unwrap();
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.states.len(), 13);
+        assert_eq!(path.states[0], (0, 0));
+
+        let weighted = |p: &(i32, i32)| neighbours(p).map(|n| (n, 1));
+        let plain = dijkstra((0, 0), weighted, goal);
+        let guided = astar((0, 0), weighted, |&(x, y)| (4 - x).abs() + y, goal);
+        assert_eq!(plain.path.unwrap().cost, 12);
+        assert_eq!(guided.path.unwrap().cost, 12);
+        assert!(guided.stats.expanded <= plain.stats.expanded);
+
+        assert!(bfs((0, 0), neighbours, |&p| p == (9, 9)).path.is_none());
+    }
+}
@@ -1,27 +1,50 @@
+//! Day 1: Sonar Sweep
+
 use itertools::izip;
 
+use crate::answer::Answer;
+use crate::error::SolveError;
 use crate::common;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<i128>;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 1, title: "Sonar Sweep", unsolved: &[], input: InputMode::Normalized }
     }
-}
 
-fn star1(input: String) -> i128 {
-    let depths = common::read_integers(&input).unwrap();
+    fn parse(&self, input: &str) -> Result<Vec<i128>, SolveError> {
+        let depths = common::read_integers(input)?;
+        if depths.is_empty() {
+            return Err(SolveError::at(input, input, "expected a depth on every line"));
+        }
 
-    izip!(&depths, &depths[1..])
-        .filter(|(d1, d2)| d1 < d2)
-        .count() as _
+        Ok(depths)
+    }
+
+    fn part1(&self, depths: &Vec<i128>) -> Result<Answer, SolveError> {
+        Ok(star1(depths))
+    }
+
+    fn part2(&self, depths: &Vec<i128>) -> Result<Answer, SolveError> {
+        Ok(star2(depths))
+    }
 }
 
-fn star2(input: String) -> i128 {
-    let depths = common::read_integers(&input).unwrap();
+fn star1(depths: &[i128]) -> Answer {
+    izip!(depths, depths.iter().skip(1))
+        .filter(|(d1, d2)| d1 < d2)
+        .count()
+        .into()
+}
 
-    izip!(&depths, &depths[3..])
+fn star2(depths: &[i128]) -> Answer {
+    izip!(depths, depths.iter().skip(3))
         .filter(|(d1, d2)| d1 < d2)
-        .count() as _
+        .count()
+        .into()
 }
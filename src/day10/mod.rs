@@ -1,15 +1,43 @@
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+//! Day 10: Syntax Scoring
+
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<String>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 10, title: "Syntax Scoring", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<String>, SolveError> {
+        if let Some((i, c)) = input.char_indices().find(|&(_, c)| !c.is_whitespace() && !"()[]{}<>".contains(c)) {
+            return Err(SolveError::at(input, &input[i..], format!("expected a bracket, got `{}`", c)));
+        }
+
+        Ok(input.split_whitespace()
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    fn part1(&self, lines: &Vec<String>) -> Result<Answer, SolveError> {
+        Ok(star1(lines))
+    }
+
+    fn part2(&self, lines: &Vec<String>) -> Result<Answer, SolveError> {
+        star2(lines)
     }
 }
 
-fn star1(input: String) -> i128 {
-    input.split_whitespace()
+fn star1(lines: &[String]) -> Answer {
+    lines.iter()
         .map(|line| score_error(line))
-        .sum()
+        .sum::<i128>()
+        .into()
 }
 
 fn score_error(line: &str) -> i128 {
@@ -18,28 +46,30 @@ fn score_error(line: &str) -> i128 {
 
     let mut stack: Vec<char> = Vec::new();
     for c in line.chars() {
-        match c {
-            c if ['(', '[', '{', '<'].contains(&c) => stack.push(c),
-            c if [')', ']', '}', '>'].contains(&c) => {
-                if match_map[&c] != stack.pop().unwrap() {
-                    return score_map[&c];
-                }
+        match match_map.get(&c) {
+            // A closer with nothing left to close corrupts the line too
+            Some(&open) => if stack.pop() != Some(open) {
+                return score_map[&c];
             },
-            _ => unreachable!(),
+            None => stack.push(c),
         }
     }
 
     0
 }
 
-fn star2(input: String) -> i128 {
-    let mut scores: Vec<i128> = input.split_whitespace()
+fn star2(lines: &[String]) -> Result<Answer, SolveError> {
+    let mut scores: Vec<i128> = lines.iter()
         .map(|line| score_complete(line))
         .filter(|&s| s != 0)
         .collect();
 
+    if scores.is_empty() {
+        return Err(SolveError::NoSolution("no line is incomplete".to_string()));
+    }
+
     scores.sort();
-    scores[scores.len() / 2]
+    Ok(scores[scores.len() / 2].into())
 }
 
 fn score_complete(line: &str) -> i128 {
@@ -48,21 +78,18 @@ fn score_complete(line: &str) -> i128 {
 
     let mut stack: Vec<char> = Vec::new();
     for c in line.chars() {
-        match c {
-            c if ['(', '[', '{', '<'].contains(&c) => stack.push(c),
-            c if [')', ']', '}', '>'].contains(&c) => {
-                if match_map[&c] != stack.pop().unwrap() {
-                    return 0;
-                }
+        match match_map.get(&c) {
+            Some(&open) => if stack.pop() != Some(open) {
+                return 0;
             },
-            _ => unreachable!(),
+            None => stack.push(c),
         }
     }
 
     let mut total = 0;
-    while !stack.is_empty() {
+    while let Some(open) = stack.pop() {
         total *= 5;
-        total += score_map[&stack.pop().unwrap()];
+        total += score_map[&open];
     }
 
     total
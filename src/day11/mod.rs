@@ -1,125 +1,112 @@
-use std::collections::HashMap;
+//! Day 11: Dumbo Octopus
+
 use std::collections::HashSet;
 
-use itertools::Itertools;
+use log::trace;
+
+use crate::answer::Answer;
+use crate::common::automaton::{Automaton, Engine};
+use crate::common::grid::{Grid, Pos};
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
 
-type Pos = (i128, i128);
-type Map = HashMap<Pos, i128>;
+type Map = Grid<u32>;
 type Set = HashSet<Pos>;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+#[derive(Default)]
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Map;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 11, title: "Dumbo Octopus", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Map, SolveError> {
+        Grid::parse(input, "a digit", |c| c.to_digit(10))
+    }
+
+    fn part1(&self, map: &Map) -> Result<Answer, SolveError> {
+        Ok(star1(map))
+    }
+
+    fn part2(&self, map: &Map) -> Result<Answer, SolveError> {
+        Ok(star2(map))
     }
 }
 
-fn star1(input: String) -> i128 {
-    let (mut map, _) = parse_input(&input);
+/// The octopuses' energy levels, and how many of them flashed on the
+/// last step.
+#[derive(Clone)]
+struct Octopuses {
+    energy: Map,
+    flashed: usize,
+}
 
-    let mut total: i128 = 0;
-    for _ in 0..100 {
-        let (new_map, count) = evolve(map);
-        map = new_map;
-        total += count;
+impl Automaton for Octopuses {
+    fn step(&mut self) -> bool {
+        self.flashed = evolve(&mut self.energy);
+        true
     }
+}
 
-    total
+fn engine(map: &Map) -> Engine<'static, Octopuses> {
+    Engine::new(Octopuses { energy: map.clone(), flashed: 0 })
+        .on_step(|step, octopuses| trace!("after step {}:\n{}", step, octopuses.energy))
 }
 
-fn parse_input(input: &str) -> (Map, i128) {
-    let dim = input.split_whitespace().count() as i128;
-    let map = input.split_whitespace()
-        .enumerate()
-        .flat_map(|(y, line)|
-            line.chars()
-                .enumerate()
-                .map(move |(x, c)| 
-                    (
-                        (x as i128, y as i128), 
-                        c.to_digit(10).unwrap() as i128
-                    )
-                )
-        )
-        .collect();
-
-    (map, dim)
+fn star1(map: &Map) -> Answer {
+    let mut engine = engine(map);
+
+    (0..100)
+        .map(|_| {
+            engine.step();
+            engine.state().flashed
+        })
+        .sum::<usize>()
+        .into()
 }
 
-fn evolve(map: Map) -> (Map, i128) {
-    let mut map: Map = map.into_iter()
-        .map(|(k, v)| (k, v + 1))
-        .collect();
+/// Runs one step, returning how many octopuses flashed.
+fn evolve(map: &mut Map) -> usize {
+    for pos in map.positions() {
+        map[pos] += 1;
+    }
 
     let mut closed = Set::new();
-    let mut count = 0;
     loop {
-        let open = get_open(&map, &closed);
+        let open = get_open(map, &closed);
         if open.is_empty() {
             break;
         }
 
-        count += open.len();
-        for pos in open.iter() {
-            let ns = neighbours(&map, *pos); 
+        for &pos in open.iter() {
+            let ns: Vec<Pos> = map.neighbours8(pos).collect();
             for n in ns {
-                *map.get_mut(&n).unwrap() += 1;
+                map[n] += 1;
             }
         }
 
         closed.extend(&open);
     }
 
-    let map = map.iter()
-        .map(|(pos, &v)| (*pos, if v > 9 { 0 } else { v }))
-        .collect();
-
-    (map, count as i128)
-}
+    for &pos in closed.iter() {
+        map[pos] = 0;
+    }
 
-fn neighbours(map: &Map, pos: Pos) -> Set {
-    let (x, y) = pos;
-    (x - 1..=x + 1).cartesian_product(y - 1..=y + 1)
-        .filter(|(x_, y_)| *x_ != x || *y_ != y)
-        .filter(|pos| map.contains_key(&pos))
-        .collect()
+    closed.len()
 }
 
 fn get_open(map: &Map, closed: &Set) -> Set {
     map.iter()
-        .filter(|(_, &v)| v > 9 )
-        .map(|(k, _)| k)
-        .filter(|pos| !closed.contains(pos))
-        .cloned()
+        .filter(|(pos, &v)| v > 9 && !closed.contains(pos))
+        .map(|(pos, _)| pos)
         .collect()
 }
 
-#[allow(dead_code)]
-fn display(map: &Map, dim: i128) {
-    for y in 0..dim {
-        for x in 0..dim {
-            print!("{}", map[&(x, y)]);
-        }
-        println!();
-    }
-    println!();
-} 
-
-fn star2(input: String) -> i128 {
-    let (mut map, _) = parse_input(&input);
-
-    let mut i = 1;
-    loop {
-        let (new_map, _) = evolve(map);
-        map = new_map;
-
-        if map.values().all(|&v| v == 0) {
-            return i;
-        }
-
-        i += 1;
-    }
+fn star2(map: &Map) -> Answer {
+    engine(map)
+        .run_until(|octopuses| octopuses.energy.values().all(|&v| v == 0))
+        .into()
 }
-
-
@@ -1,29 +1,54 @@
+//! Day 12: Passage Pathing
+
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
 use itertools::Itertools;
 
+use crate::answer::Answer;
+use crate::common::{memo, scan};
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
 type Graph = HashMap<String, HashSet<String>>;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+#[derive(Default)]
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Graph;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 12, title: "Passage Pathing", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Graph, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, graph: &Graph) -> Result<Answer, SolveError> {
+        Ok(star1(graph))
+    }
+
+    fn part2(&self, graph: &Graph) -> Result<Answer, SolveError> {
+        Ok(star2(graph))
     }
 }
 
-fn star1(input: String) -> i128 {
-    let graph = parse_input(&input);
-    search(&graph, "start", hashset!{"start"}, false) as i128
+fn star1(graph: &Graph) -> Answer {
+    count_paths(graph, false).into()
 }
 
-fn parse_input(input: &str) -> Graph {
-    let tuples: Vec<(&str, &str)> = input.split_whitespace()
-        .flat_map(|line| line.split('-'))
-        .tuples::<(&str, &str)>()
-        .map(|(k, v)| (k.clone(), v.clone()))
-        .collect();
+fn parse_input(input: &str) -> Result<Graph, SolveError> {
+    let mut tuples: Vec<(&str, &str)> = Vec::new();
+    for line in input.lines() {
+        let caves = scan::fields(input, line, "{}-{}")?;
+        if let Some(cave) = caves.iter().find(|cave| cave.is_empty() || !cave.chars().all(char::is_alphabetic)) {
+            return Err(SolveError::at(input, cave, format!("expected the name of a cave, got `{}`", cave)));
+        }
+        tuples.push((caves[0], caves[1]));
+    }
 
     let mut map: HashMap<String, HashSet<String>> = HashMap::new();
     for (_, g) in &tuples.into_iter().group_by(|(k, _)| *k) {
@@ -33,43 +58,36 @@ fn parse_input(input: &str) -> Graph {
         }
     }
 
-    map
-}
-
-fn search(
-    graph: &Graph, 
-    node: &str, 
-    closed: HashSet<&str>,
-    bonus: bool,
-) -> i128 {
-    let mut count = 0;
-    for other in graph.get(node).unwrap() {
-        if other.eq("end") {
-            count += 1;
-            continue;
-        }
-
-        if other.eq("start") {
-            continue;
-        }
-
-        if other.chars().all(char::is_lowercase) {
-            if !closed.contains(other as &str) {
-                let mut closed = closed.clone();
-                closed.insert(other);
-                count += search(graph, other, closed, bonus);
-            } else if bonus {
-                count += search(graph, other, closed.clone(), false);
-            }
-        } else {
-            count += search(graph, other, closed.clone(), bonus);
+    for cave in ["start", "end"] {
+        if !map.contains_key(cave) {
+            return Err(SolveError::at(input, input, format!("expected a cave called `{}`", cave)));
         }
     }
 
-    count
+    Ok(map)
+}
+
+/// The paths from `start` to `end` that visit small caves at most once,
+/// or one of them twice if `bonus` is set.
+fn count_paths(graph: &Graph, bonus: bool) -> i128 {
+    memo::recursive(("start", BTreeSet::from(["start"]), bonus), |memo, (node, visited, bonus)| {
+        graph[node].iter()
+            .map(|other| match other.as_str() {
+                "end" => 1,
+                "start" => 0,
+                other if !other.chars().all(char::is_lowercase) => memo.get((other, visited.clone(), bonus)),
+                other if !visited.contains(other) => {
+                    let mut visited = visited.clone();
+                    visited.insert(other);
+                    memo.get((other, visited, bonus))
+                }
+                other if bonus => memo.get((other, visited.clone(), false)),
+                _ => 0,
+            })
+            .sum()
+    })
 }
 
-fn star2(input: String) -> i128 {
-    let graph = parse_input(&input);
-    search(&graph, "start", hashset!{"start"}, true) as i128
+fn star2(graph: &Graph) -> Answer {
+    count_paths(graph, true).into()
 }
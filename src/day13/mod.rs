@@ -1,41 +1,56 @@
+//! Day 13: Transparent Origami
+
 use std::collections::HashSet;
 
-use itertools::Itertools;
+use crate::answer::Answer;
+use crate::common::scan;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
 
 type Pos = (i128, i128);
 type Fold = (char, i128);
+type Paper = (HashSet<Pos>, Vec<Fold>);
+
+#[derive(Default)]
+pub struct Day13;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+impl Solution for Day13 {
+    type Input = Paper;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 13, title: "Transparent Origami", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Paper, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, paper: &Paper) -> Result<Answer, SolveError> {
+        Ok(star1(paper))
     }
-}
 
-fn star1(input: String) -> i128 {
-    let (dots, folds) = parse_input(&input);
-    fold(dots, folds[0]).len() as i128
+    fn part2(&self, paper: &Paper) -> Result<Answer, SolveError> {
+        Ok(star2(paper))
+    }
 }
 
-fn parse_input(input: &str) -> (HashSet<Pos>, Vec<Fold>) {
-    let (dots, folds) = input.split("\n\n")
-        .tuples::<(&str, &str)>()
-        .next()
-        .unwrap();
+fn star1(paper: &Paper) -> Answer {
+    let (dots, folds) = paper;
+    fold(dots.clone(), folds[0]).len().into()
+}
 
-    let dots: HashSet<Pos> = dots.split_whitespace()
-        .map(|line| line.split(",").tuples::<(&str, &str)>().next().unwrap())
-        .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-        .collect();
+fn parse_input(input: &str) -> Result<Paper, SolveError> {
+    let sections = scan::sections(input, input, 2)?;
+    let dots = scan::lines_matching(input, sections[0], "{},{}")?.into_iter().collect();
+    let folds = scan::lines_matching(input, sections[1], "fold along {}={}")?;
 
-    let folds: Vec<Fold> = folds.split_whitespace()
-        .tuples::<(&str, &str, &str)>()
-        .map(|(_, _, s)| s.split("=").tuples::<(&str, &str)>().next().unwrap())
-        .map(|(c, v)| (c.chars().next().unwrap(), v.parse().unwrap()))
-        .collect();
+    for (line, &(axis, _)) in sections[1].lines().zip(folds.iter()) {
+        if axis != 'x' && axis != 'y' {
+            return Err(SolveError::at(input, &line["fold along ".len()..], format!("expected `x` or `y`, got `{}`", axis)));
+        }
+    }
 
-    (dots, folds)
+    Ok((dots, folds))
 }
 
 fn fold(dots: HashSet<Pos>, fold: Fold) -> HashSet<Pos> {
@@ -51,8 +66,9 @@ fn fold(dots: HashSet<Pos>, fold: Fold) -> HashSet<Pos> {
         .collect()
 }
 
-fn star2(input: String) -> i128 {
-    let (mut dots, folds) = parse_input(&input);
+fn star2(paper: &Paper) -> Answer {
+    let (dots, folds) = paper;
+    let mut dots = dots.clone();
 
     for f in folds.iter() {
         dots = fold(dots, *f);
@@ -68,13 +84,12 @@ fn star2(input: String) -> i128 {
         .max()
         .unwrap();
 
-    println!();
-    for y in 0..=max_y {
-        for x in 0..=max_x {
-            print!("{}", if dots.contains(&(x, y)) { "#" } else { "." });
-        }
-        println!();
-    }
+    let rows = (0..=max_y)
+        .map(|y|
+            (0..=max_x)
+                .map(|x| if dots.contains(&(x, y)) { '#' } else { '.' })
+                .collect::<String>()
+        );
 
-    0
+    Answer::art(rows)
 }
@@ -1,42 +1,95 @@
-use std::collections::HashMap;
+//! Day 14: Extended Polymerization
+
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
+use crate::answer::Answer;
+use crate::common::scan::{self, FromInput};
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
 type State = HashMap<(char, char), i128>;
 type Rules = HashMap<(char, char), char>;
+type Polymer = (State, Rules, char);
+
+#[derive(Default)]
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Polymer;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 14, title: "Extended Polymerization", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Polymer, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, polymer: &Polymer) -> Result<Answer, SolveError> {
+        Ok(star1(polymer))
+    }
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+    fn part2(&self, polymer: &Polymer) -> Result<Answer, SolveError> {
+        Ok(star2(polymer))
     }
 }
 
-fn star1(input: String) -> i128 {
-    run(&input, 10)
+fn star1(polymer: &Polymer) -> Answer {
+    run(polymer, 10).into()
 }
 
-fn parse_input(input: &str) -> (State, Rules, char) {
-    let (template, rules) = input.split("\n\n")
-        .tuples::<(&str, &str)>()
-        .next()
-        .unwrap();
-
-    let rules = rules.split_whitespace()
-        .tuples::<(&str, &str, &str)>()
-        .map(|(k, _, v)| (
-            k.chars().tuples::<(char, char)>().next().unwrap(), 
-            v.chars().next().unwrap())
-        )
+/// Two adjacent elements, as on the left of a rule.
+struct Pair(char, char);
+
+impl FromInput for Pair {
+    fn from_input(input: &str, fragment: &str) -> Result<Pair, SolveError> {
+        fragment.chars().collect_tuple()
+            .map(|(a, b)| Pair(a, b))
+            .ok_or_else(|| SolveError::at(input, fragment, format!("expected a pair of elements, got `{}`", fragment)))
+    }
+}
+
+fn parse_input(input: &str) -> Result<Polymer, SolveError> {
+    let sections = scan::sections(input, input, 2)?;
+    let template = sections[0];
+    let first = template.chars().next()
+        .ok_or_else(|| SolveError::at(input, template, "expected a polymer template"))?;
+
+    let parsed = scan::lines_matching::<(Pair, char)>(input, sections[1], "{} -> {}")?;
+    let lines: HashMap<(char, char), &str> = parsed.iter()
+        .map(|(Pair(a, b), _)| (*a, *b))
+        .zip(sections[1].lines())
         .collect();
+    let rules: Rules = parsed.into_iter()
+        .map(|(Pair(a, b), c)| ((a, b), c))
+        .collect();
+
+    // Every pair that can turn up needs a rule, or a pass couldn't insert
+    // anything between it. A missing one is blamed on the template or the
+    // rule that first produces the pair.
+    let mut open: Vec<((char, char), &str)> = template.char_indices().tuple_windows()
+        .map(|((i, a), (_, b))| ((a, b), &template[i..]))
+        .collect();
+    let mut seen = HashSet::new();
+    while let Some(((a, b), origin)) = open.pop() {
+        if !seen.insert((a, b)) {
+            continue;
+        }
+
+        let c = *rules.get(&(a, b))
+            .ok_or_else(|| SolveError::at(input, origin, format!("no rule for the pair `{}{}`", a, b)))?;
+        open.push(((a, c), lines[&(a, b)]));
+        open.push(((c, b), lines[&(a, b)]));
+    }
 
     let mut state = State::new();
     for pair in template.chars().tuple_windows() {
         *state.entry(pair).or_default() += 1;
     }
 
-    (state, rules, template.chars().next().unwrap())
+    Ok((state, rules, first))
 }
 
 fn pass(state: &State, rules: &Rules) -> State {
@@ -63,17 +116,17 @@ fn score(state: &State, first: char) -> i128 {
     mx - mn
 }
 
-fn run(input: &str, passes: u32) -> i128 {
-    let (state, rules, first) = parse_input(&input);
+fn run(polymer: &Polymer, passes: u32) -> i128 {
+    let (state, rules, first) = polymer;
 
-    let mut state = state;
+    let mut state = state.clone();
     for _ in 0..passes {
-        state = pass(&state, &rules);
+        state = pass(&state, rules);
     }
 
-    score(&state, first)
+    score(&state, *first)
 }
 
-fn star2(input: String) -> i128 {
-    run(&input, 40)
+fn star2(polymer: &Polymer) -> Answer {
+    run(polymer, 40).into()
 }
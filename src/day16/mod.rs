@@ -1,41 +1,86 @@
-use itertools::Itertools;
+//! Day 16: Packet Decoder
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Packet;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 16, title: "Packet Decoder", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Packet, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, packet: &Packet) -> Result<Answer, SolveError> {
+        Ok(packet.version_sum().into())
+    }
+
+    fn part2(&self, packet: &Packet) -> Result<Answer, SolveError> {
+        Ok(packet.evaluate().into())
     }
 }
 
-struct Packet {
-    version: i128,
-    ptype: i128,
-    packets: Vec<Packet>,
-    value: i128,
-    length: usize,
+/// A decoded BITS packet with its sub-packets.
+pub struct Packet {
+    pub version: i128,
+    /// Type ID, 4 for literals and an operator otherwise
+    pub ptype: i128,
+    pub packets: Vec<Packet>,
+    /// The value of a literal, 0 for operators
+    pub value: i128,
+    /// Length in bits, including sub-packets
+    pub length: usize,
 }
 
+type Bits<'a> = std::str::Chars<'a>;
+
 impl Packet {
-    fn parse(bit_str: &mut std::str::Chars) -> Packet {
-        let version: String = bit_str.take(3).collect();
-        let version = i128::from_str_radix(&version, 2).unwrap();
-        let ptype: String = bit_str.take(3).collect();
-        let ptype = i128::from_str_radix(&ptype, 2).unwrap();
+    /// Decodes a packet from its hexadecimal transmission.
+    pub fn decode(hex: &str) -> Result<Packet, SolveError> {
+        parse_input(hex)
+    }
 
+    /// Sum of the versions of this packet and all of its sub-packets.
+    pub fn version_sum(&self) -> i128 {
+        self.version + self.packets.iter().map(Packet::version_sum).sum::<i128>()
+    }
+
+    #[cfg(test)]
+    fn parse(bit_str: &mut Bits) -> Packet {
+        Packet::read(bit_str).unwrap()
+    }
+
+    /// Reads a packet off the bits, failing if they run out or don't
+    /// describe a packet that can be evaluated.
+    fn read(bit_str: &mut Bits) -> Result<Packet, String> {
+        let version = take(bit_str, 3)? as i128;
+        let ptype = take(bit_str, 3)? as i128;
+
+        let packet = match ptype {
+            4 => parse_literal(version, ptype, bit_str)?,
+            _ => match take(bit_str, 1)? {
+                0 => parse_bitcount(version, ptype, bit_str)?,
+                _ => parse_packet_count(version, ptype, bit_str)?,
+            },
+        };
+
+        let count = packet.packets.len();
         match ptype {
-            4 => parse_literal(version, ptype, bit_str),
-            _ => {
-                match bit_str.next().unwrap() {
-                    '0' => parse_bitcount(version, ptype, bit_str),
-                    '1' => parse_packet_count(version, ptype, bit_str),
-                    _ => panic!("Unknown length type"),
-                }
-            }
-        }        
-    }
-
-    fn evaluate(&self) -> i128 {
+            0..=3 if count == 0 => Err(format!("operator of type {} has no sub-packets", ptype)),
+            5..=7 if count != 2 => Err(format!("comparison of type {} has {} sub-packets instead of 2", ptype, count)),
+            _ => Ok(packet),
+        }
+    }
+
+    /// The value of the expression the packet encodes.
+    pub fn evaluate(&self) -> i128 {
         match self.ptype {
             0 => self.packets.iter().map(|p| p.evaluate()).sum(),
             1 => self.packets.iter().map(|p| p.evaluate()).product(),
@@ -50,84 +95,88 @@ impl Packet {
     }
 }
 
-fn parse_literal(version: i128, ptype: i128, bit_str: &mut std::str::Chars) -> Packet {
-    let mut bits: Vec<char> = Vec::new();
+/// Reads a number of `count` bits.
+fn take(bit_str: &mut Bits, count: usize) -> Result<usize, String> {
+    let bits: String = bit_str.take(count).collect();
+    if bits.len() < count {
+        return Err("the transmission ends in the middle of a packet".to_string());
+    }
+
+    Ok(usize::from_str_radix(&bits, 2).unwrap())
+}
+
+fn parse_literal(version: i128, ptype: i128, bit_str: &mut Bits) -> Result<Packet, String> {
+    let mut value: i128 = 0;
     let mut nibbles = 0;
     loop {
-        let flag = bit_str.next().unwrap();
-        bits.extend(bit_str.take(4));
+        let flag = take(bit_str, 1)?;
+        let nibble = take(bit_str, 4)? as i128;
+        value = value.checked_mul(16)
+            .map(|v| v + nibble)
+            .ok_or("literal value is too large")?;
         nibbles += 1;
-        if flag == '0' {
+        if flag == 0 {
             break;
         }
     }
-    let bits: String = bits.into_iter().collect();
-    let value = i128::from_str_radix(&bits, 2).unwrap();              
 
-    Packet { 
-        version: version,
-        ptype: ptype,
+    Ok(Packet { 
+        version,
+        ptype,
         packets: Vec::new(),
-        value: value,
+        value,
         length: 6 + nibbles * 5,
-    }
+    })
 }
 
-fn parse_bitcount(version: i128, ptype: i128, bit_str: &mut std::str::Chars) -> Packet {
-    let expected_length: String = bit_str.take(15).collect();
-    let expected_length = usize::from_str_radix(&expected_length, 2).unwrap();
+fn parse_bitcount(version: i128, ptype: i128, bit_str: &mut Bits) -> Result<Packet, String> {
+    let expected_length = take(bit_str, 15)?;
 
     let mut actual_length = 0;
     let mut packets = Vec::new();
     while actual_length < expected_length {
-        let packet = Packet::parse(bit_str);
+        let packet = Packet::read(bit_str)?;
         actual_length += packet.length;
         packets.push(packet);
     }
 
     if actual_length != expected_length {
-        panic!("Unexpected packet segment length, expected {} but was {}", expected_length, actual_length);
+        return Err(format!("sub-packets take {} bits instead of the {} announced", actual_length, expected_length));
     }
     
     let length = 22 + packets.iter().map(|p| p.length).sum::<usize>(); 
 
-    Packet { 
-        version: version,
-        ptype: ptype,
-        packets: packets,
+    Ok(Packet { 
+        version,
+        ptype,
+        packets,
         value: -1,
-        length: length,
-    }
+        length,
+    })
 }
 
-fn parse_packet_count(version: i128, ptype: i128, bit_str: &mut std::str::Chars) -> Packet {
-    let packet_count: String = bit_str.take(11).collect();
-    let packet_count = usize::from_str_radix(&packet_count, 2).unwrap();
+fn parse_packet_count(version: i128, ptype: i128, bit_str: &mut Bits) -> Result<Packet, String> {
+    let packet_count = take(bit_str, 11)?;
 
     let mut packets = Vec::new();
     for _ in 0..packet_count {
-        packets.push(Packet::parse(bit_str));
+        packets.push(Packet::read(bit_str)?);
     }
 
     let length = 18 + packets.iter().map(|p| p.length).sum::<usize>(); 
 
-    Packet { 
-        version: version,
-        ptype: ptype,
-        packets: packets,
+    Ok(Packet { 
+        version,
+        ptype,
+        packets,
         value: -1,
-        length: length,
-    }
-}
-
-fn star1(input: String) -> i128 {
-    version_sum_for_input(&input)
+        length,
+    })
 }
 
 fn hex_str_to_binary(hex: &str) -> String {
     hex.chars()
         .map(hex_char_to_binary)
-        .intersperse("".to_string())
         .collect()
 }
 
@@ -136,24 +185,31 @@ fn hex_char_to_binary(hex: char) -> String {
     format!("{:04b}", n)
 }
 
-fn version_sum(packet: &Packet) -> i128 {
-    packet.version + packet.packets.iter().map(|p| version_sum(p)).sum::<i128>()
-}
+fn parse_input(input: &str) -> Result<Packet, SolveError> {
+    if let Some((i, c)) = input.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(SolveError::at(input, &input[i..], format!("expected a hexadecimal digit, got `{}`", c)));
+    }
+    if input.is_empty() {
+        return Err(SolveError::at(input, input, "expected a transmission"));
+    }
 
-fn version_sum_for_input(input: &str) -> i128 {
-    let bit_str = hex_str_to_binary(&input);
-    let packet = Packet::parse(&mut bit_str.chars());
-    version_sum(&packet)
+    // Errors are located at the hex digit holding the bit being read.
+    let bit_str = hex_str_to_binary(input);
+    let mut bits = bit_str.chars();
+    Packet::read(&mut bits).map_err(|message| {
+        let read = bit_str.len() - bits.as_str().len();
+        SolveError::at_offset(input, (read.saturating_sub(1) / 4).min(input.len() - 1), message)
+    })
 }
 
-fn star2(input: String) -> i128 {
-    evaluate(&input)
+#[cfg(test)]
+fn version_sum_for_input(input: &str) -> i128 {
+    parse_input(input).unwrap().version_sum()
 }
 
+#[cfg(test)]
 fn evaluate(input: &str) -> i128 {
-    let bit_str = hex_str_to_binary(&input);
-    let packet = Packet::parse(&mut bit_str.chars());
-    packet.evaluate()
+    parse_input(input).unwrap().evaluate()
 }
 
 #[cfg(test)]
@@ -1,20 +1,39 @@
+//! Day 17: Trick Shot
+
 use std::cmp::max;
 
-use regex::Regex;
+use crate::answer::Answer;
+use crate::common::scan;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
 
 type Pos = (i128, i128);
 type Target = (Pos, Pos);
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+#[derive(Default)]
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Target;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 17, title: "Trick Shot", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Target, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, target: &Target) -> Result<Answer, SolveError> {
+        Ok(star1(*target))
+    }
+
+    fn part2(&self, target: &Target) -> Result<Answer, SolveError> {
+        Ok(star2(*target))
     }
 }
 
-fn star1(input: String) -> i128 {
-    let target = parse_input(&input);
+fn star1(target: Target) -> Answer {
     let mut max_y = 0;
     for vy in 0..1000 {
         for vx in 0..1000 {
@@ -24,16 +43,12 @@ fn star1(input: String) -> i128 {
         }
     }
 
-    max_y
+    max_y.into()
 }
 
-fn parse_input(input: &str) -> Target {
-    let re = Regex::new(r"^target area: x=([-\d]+)..([-\d]+), y=([-\d]+)..([-\d]+)$").unwrap();
-    let caps = re.captures(input).unwrap();
-    (
-        (caps[1].parse().unwrap(), caps[2].parse().unwrap()),
-        (caps[3].parse().unwrap(), caps[4].parse().unwrap()),
-    )
+fn parse_input(input: &str) -> Result<Target, SolveError> {
+    let (min_x, max_x, min_y, max_y) = scan::scan(input, input, "target area: x={}..{}, y={}..{}")?;
+    Ok(((min_x, max_x), (min_y, max_y)))
 }
 
 fn inside(pos: Pos, target: Target) -> bool {
@@ -67,8 +82,7 @@ fn simulate(v0: Pos, target: Target) -> Option<i128> {
     }
 }
 
-fn star2(input: String) -> i128 {
-    let target = parse_input(&input);
+fn star2(target: Target) -> Answer {
     let mut count = 0;
     for vy in -1000..1000 {
         for vx in 0..1000 {
@@ -78,5 +92,5 @@ fn star2(input: String) -> i128 {
         }
     }
 
-    count
+    count.into()
 }
@@ -1,16 +1,79 @@
+//! Day 18: Snailfish
+
 use itertools::Itertools;
 use math::round;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = String;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 18, title: "Snailfish", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<String, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, input: &String) -> Result<Answer, SolveError> {
+        Ok(star1(input))
+    }
+
+    fn part2(&self, input: &String) -> Result<Answer, SolveError> {
+        Ok(star2(input))
+    }
+}
+
+fn star1(input: &str) -> Answer {
+    magnitude(&sum(input)).into()
+}
+
+/// Checks that every line is a reduced snailfish number: a pair whose
+/// elements are digits or pairs, nested no more than four deep.
+fn parse_input(input: &str) -> Result<String, SolveError> {
+    if input.is_empty() {
+        return Err(SolveError::at(input, input, "expected a snailfish number on every line"));
+    }
+
+    for line in input.split('\n') {
+        if !line.starts_with('[') {
+            return Err(SolveError::at(input, line, "expected a pair"));
+        }
+
+        let end = check_element(input, line, 0, 0)?;
+        if end != line.len() {
+            return Err(SolveError::at(input, &line[end..], format!("unexpected `{}` after the number", &line[end..])));
+        }
     }
+
+    Ok(input.to_string())
 }
 
-fn star1(input: String) -> i128 {
-    magnitude(&sum(&input))
+/// Checks the element starting at byte `i` of the line, returning where it ends.
+fn check_element(input: &str, line: &str, i: usize, depth: usize) -> Result<usize, SolveError> {
+    let expected = |i: usize, what: &str| SolveError::at(input, &line[i..], format!("expected {}", what));
+    match line.as_bytes().get(i) {
+        Some(b'0'..=b'9') => Ok(i + 1),
+        Some(b'[') if depth == 4 => Err(SolveError::at(input, &line[i..], "pair nested more than four deep")),
+        Some(b'[') => {
+            let i = check_element(input, line, i + 1, depth + 1)?;
+            if line.as_bytes().get(i) != Some(&b',') {
+                return Err(expected(i, "`,`"));
+            }
+            let i = check_element(input, line, i + 1, depth + 1)?;
+            if line.as_bytes().get(i) != Some(&b']') {
+                return Err(expected(i, "`]`"));
+            }
+            Ok(i + 1)
+        }
+        _ => Err(expected(i, "a digit or `[`")),
+    }
 }
 
 fn sum(input: &str) -> String {
@@ -39,11 +102,7 @@ fn reduce(input: &str) -> String {
 }
 
 fn explode(input: &str) -> Option<String> {
-    let maybe_bounds = find_explodable_pair(input);
-    if maybe_bounds.is_none() {
-        return None;
-    }
-    let (start, end) = maybe_bounds.unwrap();
+    let (start, end) = find_explodable_pair(input)?;
     let (a, b) = parse_pair(&input[start..=end]);
 
     let mut new_string = input.to_string();
@@ -104,11 +163,11 @@ fn parse_pair(input: &str) -> (i32, i32) {
 fn find_number_left(input: &str, index: usize) -> Option<(usize, usize)> {
     let mut end = -1;
     for (i, c) in input[0..index].chars().enumerate().collect::<Vec<_>>().iter().rev() {
-        if end == -1 && c.is_digit(10) {
+        if end == -1 && c.is_ascii_digit() {
             end = *i as i32;
         }
 
-        if end != -1 && !c.is_digit(10) {
+        if end != -1 && !c.is_ascii_digit() {
             return Some((i + 1, end as usize));
         }
     }
@@ -120,11 +179,11 @@ fn find_number_right(input: &str, index: usize) -> Option<(usize, usize)> {
     let mut start = -1;
     for (i, c) in input[(index + 1)..].chars().enumerate() {
         let i = i + index + 1;
-        if start == -1 && c.is_digit(10) {
+        if start == -1 && c.is_ascii_digit() {
             start = i as i32;
         }
 
-        if start != -1 && !c.is_digit(10) {
+        if start != -1 && !c.is_ascii_digit() {
             return Some((start as usize, i - 1));
         }
     }
@@ -148,11 +207,11 @@ fn split(input: &str) -> Option<String> {
 fn find_splittable_number(input: &str) -> Option<(usize, usize)> {
     let mut start = -1;
     for (i, c) in input.chars().enumerate() {
-        if start == -1 && c.is_digit(10) {
+        if start == -1 && c.is_ascii_digit() {
             start = i as i32;
         }
 
-        if start != -1 && !c.is_digit(10) {
+        if start != -1 && !c.is_ascii_digit() {
             if start as usize == i - 1 {
                 start = -1;
                 continue;
@@ -169,7 +228,7 @@ fn magnitude(input: &str) -> i128 {
     let chars: Vec<_> = input.chars().collect();
     
     let index;
-    let a = if chars[1].is_digit(10) {
+    let a = if chars[1].is_ascii_digit() {
         index = 3;
         chars[1].to_digit(10).unwrap() as i128
     } else {
@@ -178,7 +237,7 @@ fn magnitude(input: &str) -> i128 {
         magnitude(&input[1..=bi])
     };
 
-    let b = if chars[index].is_digit(10) {
+    let b = if chars[index].is_ascii_digit() {
         chars[index].to_digit(10).unwrap() as i128
     } else {
         let bi = find_matching_bracket(input, index);
@@ -209,12 +268,13 @@ fn find_matching_bracket(input: &str, index: usize) -> usize {
     panic!("input string {} unbalanced", input);
 }
 
-fn star2(input: String) -> i128 {
+fn star2(input: &str) -> Answer {
     let numbers: Vec<_> = input.split_whitespace().collect();
     numbers.iter().cartesian_product(numbers.iter())
         .map(|(x, y)| magnitude(&add(x, y)))
         .max()
         .unwrap()
+        .into()
 }
 
 
@@ -224,9 +284,9 @@ mod tests {
 
     #[test]
     fn test_explode() {
-        assert_eq!(explode("").is_some(), false);
-        assert_eq!(explode("10").is_some(), false);
-        assert_eq!(explode("[10,20]").is_some(), false);
+        assert!(explode("").is_none());
+        assert!(explode("10").is_none());
+        assert!(explode("[10,20]").is_none());
 
         assert_exploded("[[[[[10,20]]]]]", "[[[[0]]]]");
         assert_exploded("[[[[[9,8],12],2],3],4]", "[[[[0,20],2],3],4]");
@@ -287,14 +347,14 @@ mod tests {
 
     fn assert_exploded(input: &str, expected: &str) {
         let exploded = explode(input);
-        assert_eq!(exploded.is_some(), true);
+        assert!(exploded.is_some());
         let exploded = exploded.unwrap();
         assert_eq!(exploded, expected);
     }
 
     fn assert_split(input: &str, expected: &str) {
         let splitted = split(input);
-        assert_eq!(splitted.is_some(), true);
+        assert!(splitted.is_some());
         let splitted = splitted.unwrap();
         assert_eq!(splitted, expected);
     }
@@ -1,15 +1,22 @@
+//! Day 19: Beacon Scanner
+
 use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 use nalgebra::{Vector3, Rotation3};
 
-type Pos = Vector3<i128>;
-type Rotation = Rotation3<f64>;
+use crate::answer::Answer;
+use crate::common::scan;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+pub type Pos = Vector3<i128>;
+pub type Rotation = Rotation3<f64>;
 
 fn rotations() -> Vec<Rotation> {
     let rad90 = std::f64::consts::FRAC_PI_2;
 
-    let rotations = vec![
+    let rotations = [
         0.0 * Vector3::y() * rad90,
         1.0 * Vector3::y() * rad90,
         2.0 * Vector3::y() * rad90,
@@ -42,14 +49,19 @@ fn rotate(pos: Pos, rotation: Rotation) -> Pos {
     )
 }
 
+/// The beacons a scanner sees, relative to the scanner.
 #[derive(Eq, PartialEq, Hash, Clone)]
-struct Scanner {
+pub struct Scanner {
     beacons: Vec<Pos>,
 }
 
 impl Scanner {
-    fn new(beacons: Vec<Pos>) -> Scanner {
-        Scanner { beacons: beacons }
+    pub fn new(beacons: Vec<Pos>) -> Scanner {
+        Scanner { beacons }
+    }
+
+    pub fn beacons(&self) -> &[Pos] {
+        &self.beacons
     }
 
     fn len(&self) -> usize {
@@ -67,7 +79,7 @@ impl Scanner {
             .collect()
     }
 
-    fn rotate(&self, rotation: Rotation) -> Scanner {
+    pub fn rotate(&self, rotation: Rotation) -> Scanner {
         let beacons = self.beacons.iter()
             .map(|&pos| rotate(pos, rotation))
             .collect();
@@ -78,7 +90,7 @@ impl Scanner {
     /// Checks if the scanner has at least 12 beacons in common
     /// with another scanner, and if so, returns the relative
     /// position of other relative to this scanner
-    fn locate(&self, other: &Scanner) -> Option<(Pos, Rotation)> {
+    pub fn locate(&self, other: &Scanner) -> Option<(Pos, Rotation)> {
         for i in 0..self.len() {
             let norm_a = self.normalize(i);
             for j in 0..other.len() {
@@ -98,21 +110,31 @@ impl Scanner {
     }
 }
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+#[derive(Default)]
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Vec<Scanner>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 19, title: "Beacon Scanner", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<Scanner>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, scanners: &Vec<Scanner>) -> Result<Answer, SolveError> {
+        star1(scanners)
     }
-}
 
-fn star1(input: String) -> i128 {
-    solve1(&input)
+    fn part2(&self, scanners: &Vec<Scanner>) -> Result<Answer, SolveError> {
+        star2(scanners)
+    }
 }
 
-fn solve1(input: &str) -> i128 {
-    let scanners = parse_input(input);
-    let locations = locate_scanners(scanners[0].clone(), scanners);
+fn star1(scanners: &[Scanner]) -> Result<Answer, SolveError> {
+    let locations = locate_all(scanners)?;
 
     let beacons: HashSet<Pos> = locations.iter()
         .flat_map(|(s, pos)| 
@@ -121,36 +143,43 @@ fn solve1(input: &str) -> i128 {
         )
         .collect();
 
-    beacons.len() as i128
+    Ok(beacons.len().into())
 }
 
-fn parse_input(input: &str) -> Vec<Scanner> {
-    input.split("\n\n")
-        .map(|s| {
-            let beacons = s.split("\n").skip(1)
-                .map(|v| 
-                    v.split(",")
-                        .tuples::<(&str, &str, &str)>()
-                        .map(|(x, y, z)| 
-                            Pos::new(
-                                x.parse().unwrap(),
-                                y.parse().unwrap(),
-                                z.parse().unwrap(),
-                            )
-                        )
-                        .next()
-                        .unwrap()
-                )
-                .collect();
-
-            Scanner::new(beacons)
+fn parse_input(input: &str) -> Result<Vec<Scanner>, SolveError> {
+    input.split("\n\n").enumerate()
+        .map(|(i, section)| {
+            let (header, beacons) = section.split_once('\n').unwrap_or((section, ""));
+            let (number,): (usize,) = scan::scan(input, header, "--- scanner {} ---")?;
+            if number != i {
+                return Err(SolveError::at(input, header, format!("expected scanner {}, found scanner {}", i, number)));
+            }
+
+            let beacons: Vec<(i128, i128, i128)> = scan::lines_matching(input, beacons, "{},{},{}")?;
+            if beacons.is_empty() {
+                return Err(SolveError::at(input, header, "expected the beacons the scanner sees"));
+            }
+
+            Ok(Scanner::new(beacons.into_iter().map(|(x, y, z)| Pos::new(x, y, z)).collect()))
         })
         .collect()
 }
 
+/// Locates every scanner relative to the first one.
+fn locate_all(scanners: &[Scanner]) -> Result<HashMap<Scanner, Pos>, SolveError> {
+    let locations = locate_scanners(scanners[0].clone(), scanners.to_vec());
+    if locations.len() < scanners.len() {
+        return Err(SolveError::NoSolution(format!(
+            "only {} of the {} scanners overlap enough with the others to be located", locations.len(), scanners.len(),
+        )));
+    }
+
+    Ok(locations)
+}
+
 /// Find locations and rotations of scanners relative to origin.
 /// Result map scanner keys are rotated to align with origin.
-fn locate_scanners<'a>(origin: Scanner, scanners: Vec<Scanner>) -> HashMap<Scanner, Pos> {
+pub fn locate_scanners(origin: Scanner, scanners: Vec<Scanner>) -> HashMap<Scanner, Pos> {
     let mut open = hashset!{origin.clone()};
     let mut closed: HashSet<_> = scanners.into_iter().filter(|s| s != &origin).collect();
 
@@ -161,7 +190,7 @@ fn locate_scanners<'a>(origin: Scanner, scanners: Vec<Scanner>) -> HashMap<Scann
 
         let mut new_closed = hashset!{};
         for s2 in closed.iter() {
-            if let Some((pos, rotation)) = s.locate(&s2) {
+            if let Some((pos, rotation)) = s.locate(s2) {
                 let rotated_s2 = s2.rotate(rotation);
                 let s_pos = result[&s];
                 let s2_pos = s_pos + pos;
@@ -177,16 +206,16 @@ fn locate_scanners<'a>(origin: Scanner, scanners: Vec<Scanner>) -> HashMap<Scann
     result
 }
 
-fn star2(input: String) -> i128 {
-    let scanners = parse_input(&input);
-    let locations: Vec<_> = locate_scanners(scanners[0].clone(), scanners)
+fn star2(scanners: &[Scanner]) -> Result<Answer, SolveError> {
+    let locations: Vec<_> = locate_all(scanners)?
         .values()
         .map(|pos| (pos.x, pos.y, pos.z))
         .collect();
     locations.iter().cartesian_product(locations.iter())
         .map(|((ax, ay, az), (bx, by, bz))| (ax - bx).abs() + (ay - by).abs() + (az - bz).abs())
         .max()
-        .unwrap()
+        .map(Answer::from)
+        .ok_or_else(|| SolveError::NoSolution("there are no scanners".to_string()))
 }
 
 #[cfg(test)]
@@ -249,9 +278,9 @@ mod tests {
 755,-354,-619
 553,889,-390";
 
-        let scanners = parse_input(input);
+        let scanners = parse_input(input).unwrap();
         let maybe = scanners[0].locate(&scanners[1]);
-        assert_eq!(maybe.is_some(), true);
+        assert!(maybe.is_some());
         let (pos, _) = maybe.unwrap();
         assert_eq!(pos, Pos::new(68, -1246, -43));
     }
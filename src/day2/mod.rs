@@ -1,31 +1,56 @@
+//! Day 2: Dive!
+
+use crate::answer::Answer;
+use crate::error::SolveError;
 use crate::common;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<(String, i128)>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 2, title: "Dive!", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<(String, i128)>, SolveError> {
+        common::read_labeled_integers(input)?
+            .into_iter()
+            .map(|(label, value)| match label {
+                "forward" | "up" | "down" => Ok((label.to_string(), value)),
+                _ => Err(SolveError::at(input, label, format!("unknown command `{}`", label))),
+            })
+            .collect()
+    }
+
+    fn part1(&self, commands: &Vec<(String, i128)>) -> Result<Answer, SolveError> {
+        Ok(star1(commands))
+    }
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+    fn part2(&self, commands: &Vec<(String, i128)>) -> Result<Answer, SolveError> {
+        Ok(star2(commands))
     }
 }
 
-fn star1(input: String) -> i128 {
-    let (x, y) = common::read_labeled_integers(&input).unwrap()
-        .into_iter()
-        .map(|(label, value)| match label {
-            "forward" => (value, 0),
+fn star1(commands: &[(String, i128)]) -> Answer {
+    let (x, y) = commands.iter()
+        .map(|(label, value)| match label.as_str() {
+            "forward" => (*value, 0),
             "up" => (0, -value),
-            "down" => (0, value),
+            "down" => (0, *value),
             _ => panic!("Unknown label"),
         })
         .fold((0, 0), |(sx, sy), (x, y)| (sx + x, sy + y));
 
-    x * y
+    (x * y).into()
 }
 
-fn star2(input: String) -> i128 {
+fn star2(commands: &[(String, i128)]) -> Answer {
     let mut aim = 0;
     let mut cur_pos = (0, 0);
-    for (label, value) in common::read_labeled_integers(&input).unwrap() {
+    for (label, &value) in commands.iter().map(|(label, value)| (label.as_str(), value)) {
         match label {
             "forward" => { 
                 let (x, y) = cur_pos;
@@ -38,5 +63,5 @@ fn star2(input: String) -> i128 {
     }
 
     let (x, y) = cur_pos;
-    x * y
+    (x * y).into()
 }
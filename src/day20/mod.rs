@@ -1,95 +1,118 @@
-use std::collections::HashSet;
+//! Day 20: Trench Map
+
+use log::trace;
+
+use crate::answer::Answer;
+use crate::common::automaton::{Automaton, Engine};
+use crate::common::grid::{Grid, Pos};
+use crate::common::scan;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+type Algo = Vec<bool>;
+type Puzzle = (Algo, Image);
+
+/// The part of an infinite image that has been looked at, every pixel
+/// outside of it is the background.
+#[derive(Clone)]
+pub struct Image {
+    pixels: Grid<bool>,
+    background: bool,
+}
 
-use itertools::Itertools;
+#[derive(Default)]
+pub struct Day20;
 
-type Pos = (i128, i128);
-type Image = HashSet<Pos>;
-type Algo = Vec<char>;
+impl Solution for Day20 {
+    type Input = Puzzle;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 20, title: "Trench Map", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Puzzle, SolveError> {
+        parse_input(input)
     }
-}
 
-fn star1(input: String) -> i128 {
-    let (algo, mut image) = parse_input(&input);
+    fn part1(&self, puzzle: &Puzzle) -> Result<Answer, SolveError> {
+        Ok(star1(puzzle))
+    }
 
-    for i in 0..2 {
-        image = evolve(&image, &algo, if i % 2 == 0 { '.' } else { '#' });
+    fn part2(&self, puzzle: &Puzzle) -> Result<Answer, SolveError> {
+        Ok(star2(puzzle))
     }
+}
 
-    image.len() as i128
+fn star1(puzzle: &Puzzle) -> Answer {
+    enhance(puzzle, 2)
 }
 
-fn parse_input(input: &str) -> (Algo, Image) {
-    let (algo, image) = input.split("\n\n")
-        .tuples::<(&str, &str)>()
-        .next()
-        .unwrap();
-    let algo: Algo = algo.chars().collect();
-
-    let image: Image = image.split_whitespace()
-        .enumerate()
-        .flat_map(|(y, line)|
-            line.chars().enumerate()
-                .filter(|(_, c)| *c == '#')
-                .map(move |(x, _)| (x as i128, y as i128))
-        )
-        .collect();
-
-    (algo, image)
+fn parse_input(input: &str) -> Result<Puzzle, SolveError> {
+    let sections = scan::sections(input, input, 2)?;
+    let (algo, image) = (sections[0], sections[1]);
+    if let Some((i, c)) = algo.char_indices().find(|(_, c)| !matches!(c, '#' | '.')) {
+        return Err(SolveError::at(input, &algo[i..], format!("expected `#` or `.`, got `{}`", c)));
+    }
+    if algo.len() != 512 {
+        return Err(SolveError::at(input, algo, format!("expected an algorithm of 512 pixels, found {}", algo.len())));
+    }
+    let algo: Algo = algo.chars().map(|c| c == '#').collect();
+    let pixels = Grid::parse(image, "`#` or `.`", |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
+
+    Ok((algo, Image { pixels, background: false }))
 }
 
-fn index(image: &Image, pos: Pos, default: char) -> usize {
-    let (default, non_default) = if default == '#' {('1', '0')} else {('0', '1')};
+fn index(image: &Image, pos: Pos) -> usize {
     let (px, py) = pos;
-    let mut bits = vec![];
+    let mut index = 0;
     for y in (py - 1)..=(py + 1) {
         for x in (px - 1)..=(px + 1) {
-            if image.contains(&(x, y)) {
-                bits.push(non_default);
-            } else {
-                bits.push(default);
-            }
+            let lit = image.pixels.get((x, y)).copied().unwrap_or(image.background);
+            index = index << 1 | lit as usize;
         }
     }
-    let bits: String = bits.iter().collect();
-    usize::from_str_radix(&bits, 2).unwrap()
+    index
 }
 
-fn evolve(image: &Image, algo: &Algo, default: char) -> Image {
-    let all_x: HashSet<_> = image.iter().map(|(x, _)| *x).collect();
-    let all_y: HashSet<_> = image.iter().map(|(_, y)| *y).collect();
+/// Applies the algorithm once. The image grows by a pixel on every side,
+/// and the background changes with what the algorithm makes of it.
+fn evolve(image: &Image, algo: &Algo) -> Image {
+    let pixels = Grid::from_fn(image.pixels.width() + 2, image.pixels.height() + 2, |(x, y)| {
+        algo[index(image, (x - 1, y - 1))]
+    });
+    let background = algo[if image.background { 511 } else { 0 }];
 
-    let min_x = all_x.iter().min().unwrap();
-    let max_x = all_x.iter().max().unwrap();
-    let min_y = all_y.iter().min().unwrap();
-    let max_y = all_y.iter().max().unwrap();
-
-    let mut new_image = Image::new();
-    for y in (min_y - 1)..=(max_y + 1) {
-        for x in (min_x - 1)..=(max_x + 1) {
-            let index = index(image, (x, y), default);
-            let c = algo[index];
+    Image { pixels, background }
+}
 
-            if c == default {
-                new_image.insert((x, y));
-            }
-        }
-    } 
+/// An image being enhanced again and again by the same algorithm.
+struct Enhancement<'a> {
+    algo: &'a Algo,
+    image: Image,
+}
 
-    new_image
+impl Automaton for Enhancement<'_> {
+    fn step(&mut self) -> bool {
+        self.image = evolve(&self.image, self.algo);
+        true
+    }
 }
 
-fn star2(input: String) -> i128 {
-    let (algo, mut image) = parse_input(&input);
+fn enhance(puzzle: &Puzzle, steps: usize) -> Answer {
+    let (algo, image) = puzzle;
 
-    for i in 0..50 {
-        image = evolve(&image, &algo, if i % 2 == 0 { '.' } else { '#' });
-    }
+    let mut engine = Engine::new(Enhancement { algo, image: image.clone() })
+        .on_step(|step, enhancement| trace!(
+            "after step {}:\n{}", step, enhancement.image.pixels.map(|&lit| if lit { '#' } else { '.' }),
+        ));
+
+    engine.run(steps).image.pixels.values().filter(|&&lit| lit).count().into()
+}
 
-    image.len() as i128
+fn star2(puzzle: &Puzzle) -> Answer {
+    enhance(puzzle, 50)
 }
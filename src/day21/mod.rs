@@ -1,23 +1,53 @@
+//! Day 21: Dirac Dice
+
 use std::cmp;
-use std::collections::HashMap;
 
-use itertools::Itertools;
+use crate::answer::Answer;
+use crate::common;
+use crate::common::memo;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = (i128, i128);
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 21, title: "Dirac Dice", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<(i128, i128), SolveError> {
+        let positions: Vec<i128> = input.split("\n")
+            .map(|line| {
+                let position = line.rsplit(' ').next().unwrap_or(line);
+                match common::parse(input, position)? {
+                    p @ 1..=10 => Ok(p),
+                    p => Err(SolveError::at(input, position, format!("position {} is off the board", p))),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        match positions[..] {
+            [a, b] => Ok((a, b)),
+            _ => Err(SolveError::at(input, input, "expected the starting positions of two players")),
+        }
+    }
+
+    fn part1(&self, positions: &(i128, i128)) -> Result<Answer, SolveError> {
+        Ok(star1(*positions))
+    }
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+    fn part2(&self, positions: &(i128, i128)) -> Result<Answer, SolveError> {
+        Ok(star2(*positions))
     }
 }
 
-fn star1(input: String) -> i128 {
-    let mut positions: Vec<i128> = input.split_whitespace()
-        .tuples::<(&str, &str, &str, &str, &str)>()
-        .map(|(_, _, _, _, x)| x.parse().unwrap())
-        .collect();
+fn star1(positions: (i128, i128)) -> Answer {
+    let mut positions = [positions.0, positions.1];
 
-    let mut scores = vec![0, 0];
+    let mut scores = [0, 0];
     let mut roll_count = 0;
     let mut die = 0;
     loop {
@@ -64,96 +94,38 @@ fn star1(input: String) -> i128 {
         }
     }
 
-    cmp::min(scores[0], scores[1]) * roll_count
+    (cmp::min(scores[0], scores[1]) * roll_count).into()
 }
 
-fn star2(input: String) -> i128 {
-    let positions: Vec<i128> = input.split_whitespace()
-        .tuples::<(&str, &str, &str, &str, &str)>()
-        .map(|(_, _, _, _, x)| x.parse().unwrap())
-        .collect();
-
-    let (wa, wb) = play((positions[0], positions[1]), 21);
+fn star2(positions: (i128, i128)) -> Answer {
+    let (wa, wb) = play(positions, 21);
 
-    cmp::max(wa, wb)
+    cmp::max(wa, wb).into()
 }
 
-type Args = ((i128, i128), (i128, i128), i128, bool, i128);
-type Wins = (i128, i128);
-type Cache = HashMap<Args, Wins>;
-
-fn play(initial_positions: (i128, i128), win: i128) -> (i128, i128) {
-    let mut cache: Cache = Cache::new();
-    let mut stack: Vec<Args> = Vec::new();
-
-    let initial_args = vec![
-        (initial_positions, (0, 0), 1, true, 0),
-        (initial_positions, (0, 0), 2, true, 0),
-        (initial_positions, (0, 0), 3, true, 0),
-    ];
-
-    stack.extend(initial_args.iter());
-    while !stack.is_empty() {
-        let args = stack[stack.len() - 1];
-        let ((mut pa, mut pb), (mut sa, mut sb), roll, turn, i) = args.clone();
-        if turn && i == 2 {
-            pa = (pa - 1 + roll) % 10 + 1;
-            sa += pa;
-
-            if sa >= win {
-                cache.insert(args, (1, 0));
-                stack.pop().unwrap();
-                continue;
-            } 
-        } else if !turn && i == 2 {
-            pb = (pb - 1 + roll) % 10 + 1;
-            sb += pb;
-
-            if sb >= win {
-                cache.insert(args, (0, 1));
-                stack.pop().unwrap();
-                continue;
-            } 
+/// The totals three rolls of the Dirac die can come to, and in how many
+/// universes each one happens.
+const ROLLS: [(i128, i128); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+/// The universes in which each player wins, starting from `positions`
+/// with the first player to move.
+fn play(positions: (i128, i128), win: i128) -> (i128, i128) {
+    // Wins for the player about to move and then the other, from their
+    // positions and scores.
+    memo::recursive((positions, (0, 0)), |memo, ((mover, other), (mover_score, other_score))| {
+        let mut wins = (0, 0);
+        for (roll, universes) in ROLLS {
+            let position = (mover - 1 + roll) % 10 + 1;
+            let score = mover_score + position;
+            if score >= win {
+                wins.0 += universes;
+            } else {
+                let (theirs, ours) = memo.get(((other, position), (other_score, score)));
+                wins.0 += universes * ours;
+                wins.1 += universes * theirs;
+            }
         }
 
-        let new_args = vec![
-            (
-                (pa, pb), 
-                (sa, sb), 
-                if i == 2 {0} else {roll} + 1, 
-                if i == 2 { !turn } else { turn }, 
-                (i + 1) % 3
-            ),
-            (
-                (pa, pb), 
-                (sa, sb), 
-                if i == 2 {0} else {roll} + 2, 
-                if i == 2 { !turn } else { turn }, 
-                (i + 1) % 3
-            ),
-            (
-                (pa, pb), 
-                (sa, sb), 
-                if i == 2 {0} else {roll} + 3, 
-                if i == 2 { !turn } else { turn }, 
-                (i + 1) % 3
-            ),
-        ];
-
-        if new_args.iter().all(|a| cache.contains_key(a)) {
-            let wins = new_args.iter()
-                .map(|a| cache[a])
-                .reduce(|(wa1, wb1), (wa2, wb2)| (wa1 + wa2, wb1 + wb2))
-                .unwrap();
-            cache.insert(args, wins);
-            stack.pop().unwrap();
-        } else {
-            stack.extend(new_args);
-        }
-    }
-
-    initial_args.iter()
-        .map(|a| cache[a])
-        .reduce(|(wa1, wb1), (wa2, wb2)| (wa1 + wa2, wb1 + wb2))
-        .unwrap()
+        wins
+    })
 }
@@ -1,24 +1,45 @@
+//! Day 22: Reactor Reboot
+
 use std::collections::HashSet;
 use std::collections::HashMap;
 
 use itertools::Itertools;
-use regex::Regex;
+use log::{debug, trace};
+
+use crate::answer::Answer;
+use crate::common::scan::{self, FromInput};
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<Step>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 22, title: "Reactor Reboot", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<Step>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, instructions: &Vec<Step>) -> Result<Answer, SolveError> {
+        Ok(star1(instructions))
+    }
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+    fn part2(&self, instructions: &Vec<Step>) -> Result<Answer, SolveError> {
+        Ok(star2(instructions))
     }
 }
 
 type Coord = i32;
 type Pos = (Coord, Coord, Coord);
 type Cuboid = (Pos, Pos);
+type Step = (bool, Cuboid);
 
-fn star1(input: String) -> i128 {
-    let instructions = parse_input(&input);
-
+fn star1(instructions: &[Step]) -> Answer {
     let limit = ((-50, -50, -50), (50, 50, 50));
 
     let mut map = HashSet::new();
@@ -30,35 +51,29 @@ fn star1(input: String) -> i128 {
         evolve(&mut map, *set, *cuboid, limit);
     }
 
-    map.len() as i128
+    map.len().into()
 }
 
-fn parse_input(input: &str) -> Vec<(bool, Cuboid)> {
-    let re = Regex::new(r"^x=([-\d]+)..([-\d]+),y=([-\d]+)..([-\d]+),z=([-\d]+)..([-\d]+)$").unwrap();
-
-    input.split_whitespace()
-        .tuples::<(&str, &str)>()
-        .map(|(on, cuboid)|
-            (
-                on == "on",
-                {
-                    let caps = re.captures(cuboid).unwrap();
-                    (
-                        (
-                            caps[1].parse().unwrap(),
-                            caps[3].parse().unwrap(),
-                            caps[5].parse().unwrap(),
-                        ),
-                        (
-                            caps[2].parse().unwrap(),
-                            caps[4].parse().unwrap(),
-                            caps[6].parse().unwrap(),
-                        ),
-                    )
-                }
-            )
+/// The `on`/`off` keyword at the start of a step.
+struct Switch(bool);
+
+impl FromInput for Switch {
+    fn from_input(input: &str, fragment: &str) -> Result<Switch, SolveError> {
+        match fragment {
+            "on" => Ok(Switch(true)),
+            "off" => Ok(Switch(false)),
+            _ => Err(SolveError::at(input, fragment, format!("expected `on` or `off`, got `{}`", fragment))),
+        }
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<Step>, SolveError> {
+    let steps = scan::lines_matching(input, input, "{} x={}..{},y={}..{},z={}..{}")?;
+    Ok(steps.into_iter()
+        .map(|(Switch(on), min_x, max_x, min_y, max_y, min_z, max_z)|
+            (on, ((min_x, min_y, min_z), (max_x, max_y, max_z)))
         )
-        .collect()
+        .collect())
 }
 
 fn evolve(map: &mut HashSet<Pos>, set: bool, cuboid: Cuboid, limit: Cuboid) {
@@ -100,12 +115,11 @@ fn overlaps(a: &Cuboid, b: &Cuboid) -> bool {
     min_z1 <= max_z2 && max_z1 >= min_z2
 }
 
-fn star2(input: String) -> i128 {
-    let instructions = parse_input(&input);
-    let overlapping_cuboids = instructions.iter().map(|(_, cuboid)| *cuboid).collect();
+fn star2(instructions: &[Step]) -> Answer {
+    let overlapping_cuboids: Vec<_> = instructions.iter().map(|(_, cuboid)| *cuboid).collect();
     let nonoverlapping_cuboids = subdivide(&overlapping_cuboids);
 
-    println!("Subdivision complete");
+    debug!("subdivision complete");
 
     let mut lit_set = hashset!{};
     for other in nonoverlapping_cuboids.into_iter() {
@@ -120,7 +134,7 @@ fn star2(input: String) -> i128 {
         }
     }
 
-    lit_set.iter().map(|c| len(*c)).sum::<i128>()
+    lit_set.iter().map(|c| len(*c)).sum::<i128>().into()
 }
 
 fn len(cuboid: Cuboid) -> i128 {
@@ -143,22 +157,22 @@ struct Checkpoint {
     end_ids: HashSet<usize>,
 }
 
-fn subdivide(cuboids: &Vec<Cuboid>) -> Vec<Cuboid> {
+fn subdivide(cuboids: &[Cuboid]) -> Vec<Cuboid> {
     // Find labeled intervals
     let (xcp, ycp, zcp) = checkpoints(cuboids);
 
-    println!("Checkpoints computed: ({}, {}, {})", xcp.len(), ycp.len(), zcp.len());
+    debug!("checkpoints computed: ({}, {}, {})", xcp.len(), ycp.len(), zcp.len());
 
     let xins = intervals(&xcp);
     let yins = intervals(&ycp);
     let zins = intervals(&zcp);
 
-    println!("Intervals computed: ({}, {}, {})", xins.len(), yins.len(), zins.len());
+    debug!("intervals computed: ({}, {}, {})", xins.len(), yins.len(), zins.len());
 
     let mut result = Vec::new();
     // For all triples of intervals
     for (i, zin) in zins.iter().enumerate() {
-        println!("Finished zin {} out of {}", i + 1, zins.len());
+        trace!("finished zin {} out of {}", i + 1, zins.len());
         for yin in yins.iter() {
             let zin_u_yin = &zin.ids & &yin.ids;
             for xin in xins.iter() {
@@ -168,13 +182,13 @@ fn subdivide(cuboids: &Vec<Cuboid>) -> Vec<Cuboid> {
                 }
             }
         }
-        println!("result size: {}", result.len());
+        trace!("result size: {}", result.len());
     }
 
     result
 }
 
-fn checkpoints(cuboids: &Vec<Cuboid>) -> (Vec<Checkpoint>, Vec<Checkpoint>, Vec<Checkpoint>) {
+fn checkpoints(cuboids: &[Cuboid]) -> (Vec<Checkpoint>, Vec<Checkpoint>, Vec<Checkpoint>) {
     let mut xcps = axis_checkpoints(cuboids, |((x, _, _), _)| x, |(_, (x, _, _))| x);
     xcps.sort_by_key(|cp| cp.value);
 
@@ -188,7 +202,7 @@ fn checkpoints(cuboids: &Vec<Cuboid>) -> (Vec<Checkpoint>, Vec<Checkpoint>, Vec<
 }
 
 fn axis_checkpoints(
-    cuboids: &Vec<Cuboid>, 
+    cuboids: &[Cuboid], 
     start_axis: fn (Cuboid) -> Coord,
     end_axis: fn (Cuboid) -> Coord,
 ) -> Vec<Checkpoint> {
@@ -222,7 +236,7 @@ fn axis_checkpoints(
         .collect()
 }
 
-fn intervals(checkpoints: &Vec<Checkpoint>) -> Vec<Interval> {
+fn intervals(checkpoints: &[Checkpoint]) -> Vec<Interval> {
     let mut ids = HashSet::new();
     let mut result = Vec::new();
     let mut it = checkpoints.iter().tuple_windows().peekable();
@@ -1,15 +1,43 @@
+//! Day 23: Amphipod
+
 use std::collections::{BTreeMap, HashMap};
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+use itertools::Itertools;
+use log::{debug, trace};
+
+use crate::answer::Answer;
+use crate::common::search;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Board;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 23, title: "Amphipod", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Board, SolveError> {
+        let board = parse_input(input);
+        check_burrow(input, &board)?;
+        Ok(board)
+    }
+
+    fn part1(&self, board: &Board) -> Result<Answer, SolveError> {
+        star1(board)
+    }
+
+    fn part2(&self, board: &Board) -> Result<Answer, SolveError> {
+        star2(board)
     }
 }
 
 type Pos = (i16, i16);
 type Board = BTreeMap<Pos, char>;
+type Rooms = HashMap<char, Vec<Pos>>;
 
 const SPACES_OUTSIDE_ROOMS: [Pos; 4] = [(3, 1), (5, 1), (7, 1), (9, 1)];
 const HALLWAY: [Pos; 7] = [
@@ -22,9 +50,10 @@ const HALLWAY: [Pos; 7] = [
     (11, 1),
 ];
 
-fn star1(input: String) -> i128 {
-    let board = parse_input(&input);
-    shortest_path(neighbours, zero_heuristic, board, goal(2), 2).unwrap()
+fn star1(board: &Board) -> Result<Answer, SolveError> {
+    shortest_path(neighbours, zero_heuristic, board.clone(), goal(2), 2)
+        .map(Answer::from)
+        .ok_or_else(|| SolveError::NoSolution("the amphipods can't be organized into rooms of 2".to_string()))
 }
 
 fn zero_heuristic(_board: &Board, _room_size: usize) -> i128 {
@@ -41,6 +70,47 @@ fn parse_input(input: &str) -> Board {
         .collect()
 }
 
+/// Checks the board is a burrow the solver can work with: a hallway and
+/// four rooms of the same size, and as many of each kind of amphipod as
+/// there are spaces in a room.
+fn check_burrow(input: &str, board: &Board) -> Result<(), SolveError> {
+    let lines: Vec<&str> = input.split('\n').collect();
+    for line in lines.iter() {
+        if let Some((x, c)) = line.char_indices().find(|(_, c)| !"#. ABCD".contains(*c)) {
+            return Err(SolveError::at(input, &line[x..], format!("expected `#`, `.` or an amphipod, got `{}`", c)));
+        }
+    }
+
+    let at = |(x, y): Pos, message: String| {
+        let line = lines.get(y as usize).copied().unwrap_or(input);
+        SolveError::at(input, &line[(x as usize).min(line.len())..], message)
+    };
+
+    let room_size = board.keys().map(|&(_, y)| y as usize).max().unwrap_or(0).saturating_sub(1);
+    if room_size == 0 {
+        return Err(SolveError::at(input, input, "expected a burrow with rooms below the hallway"));
+    }
+
+    let expected = goal(room_size);
+    if let Some(&pos) = expected.keys().find(|pos| !board.contains_key(pos)) {
+        return Err(at(pos, format!("expected an open space here, for rooms of {}", room_size)));
+    }
+    if let Some(&pos) = board.keys().find(|pos| !expected.contains_key(pos)) {
+        return Err(at(pos, format!("expected a wall here, for rooms of {}", room_size)));
+    }
+
+    for kind in ['A', 'B', 'C', 'D'] {
+        let count = board.values().filter(|&&c| c == kind).count();
+        if count != room_size {
+            return Err(SolveError::at(input, input, format!(
+                "expected {} amphipods of each kind, found {} of kind {}", room_size, count, kind,
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 fn goal(size: usize) -> Board {
     let mut input: String = 
         "#############\n\
@@ -54,81 +124,37 @@ fn goal(size: usize) -> Board {
     parse_input(input.as_str())
 }
 
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-
-#[derive(Clone, Eq, PartialEq)]
-struct State {
-    g: i128,
-    f: i128,
-    position: Board,
-}
-
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.f.cmp(&self.f)
-    }
-}
-
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
 fn shortest_path(
-    neighbours_fn: fn (&Board, &HashMap<char, Vec<Pos>>) -> Vec<(Board, i128)>, 
+    neighbours_fn: fn (&Board, &Rooms) -> Vec<(Board, i128)>, 
     h: fn (&Board, usize) -> i128,
     start: Board, 
     goal: Board,
     room_size: usize,
 ) -> Option<i128> {
-    println!("Start: ");
-    display(&start);
-    println!("Goal: ");
-    display(&goal);
-
-    let mut open = BinaryHeap::new();
-    open.push(State {
-        g: 0, 
-        f: h(&start, room_size as usize), 
-        position: start.clone(), 
-    });
-    let mut dist = hashmap!{ start => 0 };
-    let rooms = create_rooms(room_size);
+    debug!("start:\n{}", display(&start));
+    debug!("goal:\n{}", display(&goal));
 
-    let mut count = 0;
-    while let Some(State { g, f: _, position }) = open.pop() {
-        count += 1;
-
-        if position == goal { 
-            println!("Visited nodes: {}", count);
-            return Some(g); 
-        }
-
-        if g > dist[&position] { continue; }
-
-        for (n, n_cost) in neighbours_fn(&position, &rooms) {
-            //println!("neighbour with cost {}: ", n_cost);
-            //display(&n);
-            let tentative_g = g + n_cost;
-
-            if !dist.contains_key(&n) || tentative_g < *dist.get(&n).unwrap() {
-                //println!("Best path so far, total cost is {}, updating", tentative_g);
-                let f = tentative_g + h(&n, room_size as usize);
-                let next = State { g: tentative_g, f, position: n };
-                dist.insert(next.position.clone(), tentative_g);
-                open.push(next);
-            } else {
-                //println!("Total cost {} is worse than best path, ignoring", tentative_g);
-            }
-        }
+    let rooms = create_rooms(room_size);
+    let found = search::astar(
+        start,
+        |board| neighbours_fn(board, &rooms),
+        |board| h(board, room_size),
+        |board| *board == goal,
+    );
+    debug!(
+        "expanded {} of {} boards seen, frontier peaked at {}",
+        found.stats.expanded, found.stats.discovered, found.stats.peak_frontier,
+    );
+
+    let path = found.path?;
+    for (step, board) in path.states.iter().enumerate() {
+        trace!("step {}:\n{}", step, display(board));
     }
 
-    None
+    Some(path.cost)
 }
 
-fn neighbours(board: &Board, rooms: &HashMap<char, Vec<Pos>>) -> Vec<(Board, i128)> {
+fn neighbours(board: &Board, rooms: &Rooms) -> Vec<(Board, i128)> {
     let mut result = Vec::new();
 
     let amphipods: Vec<_> = board.iter()
@@ -187,8 +213,8 @@ fn neighbours(board: &Board, rooms: &HashMap<char, Vec<Pos>>) -> Vec<(Board, i12
             }
         }
 
-        // If amphipod is in a hallway position
-        if HALLWAY.contains(pos) {
+        // If amphipod is in a hallway position, or parked outside a room
+        if HALLWAY.contains(pos) || SPACES_OUTSIDE_ROOMS.contains(pos) {
             let room = &rooms[c];
 
             // If first room position is blocked, continue
@@ -235,34 +261,30 @@ fn cost(c: &char) -> i128 {
     }
 }
 
-fn create_rooms(room_size: usize) -> HashMap<char, Vec<Pos>> {
+fn create_rooms(room_size: usize) -> Rooms {
     hashmap!{
-        'A' => (2..(2 + room_size)).map(|y| (3 as i16, y as i16)).collect(),
-        'B' => (2..(2 + room_size)).map(|y| (5 as i16, y as i16)).collect(),
-        'C' => (2..(2 + room_size)).map(|y| (7 as i16, y as i16)).collect(),
-        'D' => (2..(2 + room_size)).map(|y| (9 as i16, y as i16)).collect(),
+        'A' => (2..(2 + room_size)).map(|y| (3_i16, y as i16)).collect(),
+        'B' => (2..(2 + room_size)).map(|y| (5_i16, y as i16)).collect(),
+        'C' => (2..(2 + room_size)).map(|y| (7_i16, y as i16)).collect(),
+        'D' => (2..(2 + room_size)).map(|y| (9_i16, y as i16)).collect(),
     }
 }
 
-fn display(board: &Board) {
+fn display(board: &Board) -> String {
     let max_y = board.keys().map(|(_, y)| *y).max().unwrap();
 
-    for y in 0..=max_y {
-        for x in 0..13 {
-            if board.contains_key(&(x, y)) {
-                print!("{}", board[&(x, y)]);
-            } else {
-                print!("#");
-            }
-        }
-        println!();
-    }
-    println!();
+    (0..=max_y)
+        .map(|y| (0..13)
+            .map(|x| board.get(&(x, y)).copied().unwrap_or('#'))
+            .collect::<String>()
+        )
+        .join("\n")
 }
 
-fn star2(input: String) -> i128 {
-    let board = parse_input(&input);
-    shortest_path(neighbours, zero_heuristic, board, goal(4), 4).unwrap()
+fn star2(board: &Board) -> Result<Answer, SolveError> {
+    shortest_path(neighbours, zero_heuristic, board.clone(), goal(4), 4)
+        .map(Answer::from)
+        .ok_or_else(|| SolveError::NoSolution("the amphipods can't be organized into rooms of 4, is the input unfolded?".to_string()))
 }
 
 #[cfg(test)]
@@ -304,7 +326,7 @@ mod tests {
             #...B.......#\n\
             ###B#C#.#D###\n\
             ###A#D#C#A#";
-        assert_eq!(is_neighbour(start, neighbour, 2, 40), true);
+        assert!(is_neighbour(start, neighbour, 2, 40));
 
         /*
         let start = neighbour;
@@ -385,10 +407,28 @@ mod tests {
         assert_map2(input, 1, 3);
     }
 
+    #[test]
+    fn test_parked_outside_room() {
+        // B stands in the space right outside its own room, where moves
+        // out of a room never stop but a board can still start.
+        let start =
+            "#############\n\
+            #....B......#\n\
+            ###A#.#C#D###\n\
+            ###A#B#C#D#";
+
+        let neighbour =
+            "#############\n\
+            #...........#\n\
+            ###A#B#C#D###\n\
+            ###A#B#C#D#";
+        assert!(is_neighbour(start, neighbour, 2, 10));
+    }
+
     fn assert_map2(input: &str, expected_score: i128, room_size: usize) {
         let board = parse_input(input);
-        let result = shortest_path(neighbours, zero_heuristic, board, goal(room_size as usize), room_size);
-        assert_eq!(result.is_some(), true);
+        let result = shortest_path(neighbours, zero_heuristic, board, goal(room_size), room_size);
+        assert!(result.is_some());
         assert_eq!(result.unwrap(), expected_score);
     }
 
@@ -1,73 +1,75 @@
-use chrono::{Datelike, Timelike, Utc};
+//! Day 24: Arithmetic Logic Unit
+
+use log::info;
 use rand::Rng;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = ();
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 24, title: "Arithmetic Logic Unit", unsolved: &[1, 2], input: InputMode::Normalized }
+    }
+
+    /// The constants the program is built from are hardcoded below
+    fn parse(&self, _: &str) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1(&self, _: &()) -> Result<Answer, SolveError> {
+        Ok(star1())
+    }
+
+    fn part2(&self, _: &()) -> Result<Answer, SolveError> {
+        Ok(star2())
     }
 }
 
-fn star1(_: String) -> i128 {
+fn star1() -> Answer {
     fish_min();
 
-    0
+    0.into()
 }
 
+#[allow(dead_code)]
 fn linear_search() {
     let mut digits = vec![8, 9, 9, 3, 7, 7, 9, 4, 9, 1, 9, 9, 3, 9];
-    let mut count = 0;
     loop {
         increment(&mut digits);
         let z = compute_z(&digits);
         if z == 0 {
-            display_time();
-            println!("Solution: {:?}", digits);
-            println!("{}", to_number(&digits));
+            info!("solution: {:?}, {}", digits, to_number(&digits));
         }
     }
 }
 
 fn linear_search_min() {
     let mut digits = vec![1, 7, 1, 1, 5, 5, 7, 4, 9, 1, 9, 1, 1, 2];
-    let mut count = 0;
     loop {
         decrement(&mut digits);
         let z = compute_z(&digits);
         if z == 0 {
-            display_time();
-            println!("Solution: {:?}", digits);
-            println!("{}", to_number(&digits));
+            info!("solution: {:?}, {}", digits, to_number(&digits));
         }
     }
 }
 
-fn display_time() {
-    let now = Utc::now();
-
-    println!(
-        "{}-{:02}-{:02} {:?} {:02}:{:02}:{:02}",
-        now.year(),
-        now.month(),
-        now.day(),
-        now.weekday(),
-        now.hour(),
-        now.minute(),
-        now.second(),
-    );
-}
-
+#[allow(dead_code)]
 fn fish() {
     let mut best = 0;
     loop {
         let solution = find_solution();
-        display_time();
-        println!("Solution: {}", solution);
+        info!("solution: {}", solution);
         if solution > best {
             best = solution;
         }
-        println!("Current best: {}", best);
+        info!("current best: {}", best);
     }
 }
 
@@ -75,12 +77,11 @@ fn fish_min() {
     let mut best = 9999_99999_99999;
     loop {
         let solution = find_solution();
-        display_time();
-        println!("Solution: {}", solution);
+        info!("solution: {}", solution);
         if solution < best {
             best = solution;
         }
-        println!("Current best: {}", best);
+        info!("current best: {}", best);
     }
 }
 
@@ -95,7 +96,7 @@ fn find_solution() -> i128 {
     }
 }
 
-fn to_number(number: &Vec<u8>) -> i128 {
+fn to_number(number: &[u8]) -> i128 {
     let s: String = number.iter()
         .map(|d| char::from_digit(*d as u32, 10).unwrap())
         .collect();
@@ -111,10 +112,10 @@ fn generate() -> Vec<u8> {
     }).collect()
 }
 
-fn minimize(digits: &Vec<u8>) -> (i128, Vec<u8>) {
+fn minimize(digits: &[u8]) -> (i128, Vec<u8>) {
     let mut prev_z = 0;
-    let mut digits = digits.clone();
-    for _ in 0..1000_000 {
+    let mut digits = digits.to_vec();
+    for _ in 0..1_000_000 {
         let (new_digits, z) = (1..digits.len())
             .map(|i| {
                 let mut digits = digits.clone();
@@ -145,7 +146,7 @@ const A: [i128; 14] = [1, 1, 1, 1, 26, 1, 26, 1, 1, 26, 26, 26, 26, 26];
 const B: [i128; 14] = [15, 15, 12, 13, -12, 10, -9, 14, 13, -14, -11, -2, -16, -14];
 const C: [i128; 14] = [15, 10, 2, 16, 12, 11, 5, 16, 6, 15, 3, 12, 10, 13];
 
-fn compute_z(number: &Vec<u8>) -> i128 {
+fn compute_z(number: &[u8]) -> i128 {
     let mut z = 0;
 
     for i in 0..14 {
@@ -157,7 +158,8 @@ fn compute_z(number: &Vec<u8>) -> i128 {
     z
 }
 
-fn increment(number: &mut Vec<u8>) {
+#[allow(dead_code)]
+fn increment(number: &mut [u8]) {
     for i in (0..14).rev() {
         if number[i] == 9 {
             number[i] = 1;
@@ -168,7 +170,7 @@ fn increment(number: &mut Vec<u8>) {
     }
 }
 
-fn decrement(number: &mut Vec<u8>) {
+fn decrement(number: &mut [u8]) {
     for i in (0..14).rev() {
         if number[i] == 1 {
             number[i] = 9;
@@ -179,8 +181,8 @@ fn decrement(number: &mut Vec<u8>) {
     }
 }
 
-fn star2(input: String) -> i128 {
+fn star2() -> Answer {
     linear_search_min();
 
-    0
+    0.into()
 }
\ No newline at end of file
@@ -1,103 +1,83 @@
-use std::collections::HashSet;
+//! Day 25: Sea Cucumber
 
-type Pos = (usize, usize);
-type Herd = HashSet<Pos>;
+use log::trace;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
-    }
-}
+use crate::answer::Answer;
+use crate::common::automaton::{Automaton, Engine};
+use crate::common::grid::Grid;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
 
-fn star1(input: String) -> i128 {
-    let (mut east, mut south, w, h) = parse_input(&input);
+/// The sea floor, `>` for the east-facing herd, `v` for the south-facing
+/// one and `.` for empty cells.
+type Floor = Grid<char>;
 
-    let mut step = 0;
-    loop {
-        step += 1;
-        let (new_east, new_south, changed) = evolve(&east, &south, w, h);
+#[derive(Default)]
+pub struct Day25;
 
-        if !changed {
-            break;
-        }
+impl Solution for Day25 {
+    type Input = Floor;
 
-        east = new_east;
-        south = new_south;
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 25, title: "Sea Cucumber", unsolved: &[2], input: InputMode::Normalized }
     }
 
-    step as i128
-}
+    fn parse(&self, input: &str) -> Result<Floor, SolveError> {
+        parse_input(input)
+    }
 
-fn parse_input(input: &str) -> (Herd, Herd, usize, usize) {
-    let mut east = Herd::new();
-    let mut south = Herd::new();
-
-    for (y, line) in input.split_whitespace().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '>' => { east.insert((x, y)); },
-                'v' => { south.insert((x, y)); },
-                _ => (),
-            }
-        }
+    fn part1(&self, floor: &Floor) -> Result<Answer, SolveError> {
+        Ok(star1(floor))
     }
 
-    let h = input.split_whitespace().count();
-    let w = input.split_whitespace().next().unwrap().chars().count();
+    fn part2(&self, _: &Floor) -> Result<Answer, SolveError> {
+        Ok(star2())
+    }
+}
 
-    (east, south, w, h)
+fn star1(floor: &Floor) -> Answer {
+    Engine::new(Herds(floor.clone()))
+        .on_step(|step, Herds(floor)| trace!("after step {}:\n{}", step, floor))
+        .run_until_stable()
+        .into()
 }
 
-fn evolve(east: &Herd, south: &Herd, w: usize, h: usize) -> (Herd, Herd, bool) {
-    let mut new_east = Herd::new();
-    let mut new_south = Herd::new();
-    let mut changed = false;
+fn parse_input(input: &str) -> Result<Floor, SolveError> {
+    Grid::parse(input, "`>`, `v` or `.`", |c| matches!(c, '>' | 'v' | '.').then_some(c))
+}
 
-    for pos in east.iter() {
-        let (x, y) = *pos;
-        let candidate = ((x + 1) % w, y);
+/// The sea floor, with the east-facing herd moving before the south-facing
+/// one on every step.
+struct Herds(Floor);
 
-        if east.contains(&candidate) || south.contains(&candidate) {
-            new_east.insert(*pos);
-        } else {
-            new_east.insert(candidate);
-            changed = true;
-        }
+impl Automaton for Herds {
+    fn step(&mut self) -> bool {
+        let (east, east_moved) = advance(&self.0, '>', (1, 0));
+        let (south, south_moved) = advance(&east, 'v', (0, 1));
+        self.0 = south;
+
+        east_moved || south_moved
     }
+}
 
-    for pos in south.iter() {
-        let (x, y) = *pos;
-        let candidate = (x, (y + 1) % h);
+/// Moves every member of a herd whose next cell, wrapping around the
+/// edges, is empty.
+fn advance(floor: &Floor, herd: char, (dx, dy): (i64, i64)) -> (Floor, bool) {
+    let mut next = floor.clone();
+    let mut changed = false;
 
-        if new_east.contains(&candidate) || south.contains(&candidate) {
-            new_south.insert(*pos);
-        } else {
-            new_south.insert(candidate);
+    for ((x, y), &c) in floor.iter() {
+        let candidate = floor.wrap((x + dx, y + dy));
+        if c == herd && floor[candidate] == '.' {
+            next[(x, y)] = '.';
+            next[candidate] = herd;
             changed = true;
         }
     }
 
-    (new_east, new_south, changed)
-}
-
-fn display(east: &Herd, south: &Herd, w: usize, h: usize) {
-    for y in 0..h {
-        for x in 0..w {
-            let pos = (x, y);
-            if east.contains(&pos) {
-                print!(">");
-            } else if south.contains(&pos) {
-                print!("v");
-            } else {
-                print!(".");
-            }
-        }
-        println!();
-    }
+    (next, changed)
 }
 
-fn star2(input: String) -> i128 {
-    0
+fn star2() -> Answer {
+    0.into()
 }
@@ -1,23 +1,46 @@
+//! Day 3: Binary Diagnostic
+
 use itertools::izip;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Vec<u32>>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 3, title: "Binary Diagnostic", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<u32>>, SolveError> {
+        input.split_whitespace()
+            .map(|line| 
+                line.char_indices()
+                    .map(|(i, c)| c.to_digit(2)
+                        .ok_or_else(|| SolveError::at(input, &line[i..], format!("expected a bit, got `{}`", c))))
+                    .collect()
+            )
+            .collect()
+    }
+
+    fn part1(&self, bit_strs: &Vec<Vec<u32>>) -> Result<Answer, SolveError> {
+        Ok(star1(bit_strs))
+    }
+
+    fn part2(&self, bit_strs: &Vec<Vec<u32>>) -> Result<Answer, SolveError> {
+        Ok(star2(bit_strs))
     }
 }
 
-fn star1(input: String) -> i128 {
-    let lines: Vec<&str> = input.split_whitespace().collect();
-    let total = lines.len() as u32;
-    let counts: Vec<u32> = lines.iter()
-        .map(|line| 
-            line.chars()
-                .map(|c| c.to_digit(2).unwrap())
-                .collect()
-        )
-        .reduce(|v1: Vec<u32>, v2| 
+fn star1(bit_strs: &[Vec<u32>]) -> Answer {
+    let total = bit_strs.len() as u32;
+    let counts: Vec<u32> = bit_strs.iter()
+        .cloned()
+        .reduce(|v1, v2| 
             izip!(v1, v2)
                 .map(|(a, b)| a + b)
                 .collect()
@@ -28,24 +51,16 @@ fn star1(input: String) -> i128 {
         .map(|&c| if c > total / 2 { '1' } else { '0' })
         .collect::<String>();
     let gamma = i128::from_str_radix(&gamma, 2).unwrap();
+    let mask = (1 << counts.len()) - 1;
 
-    gamma * (gamma ^ 4095)
+    (gamma * (gamma ^ mask)).into()
 }
 
-fn star2(input: String) -> i128 {
-    let bit_strs: Vec<Vec<u32>> = input
-        .split_whitespace()
-        .map(|line| 
-            line.chars()
-                .map(|c| c.to_digit(2).unwrap())
-                .collect()
-        )
-        .collect();
-
-    let ogr = find_rating(bit_strs.clone(), true, 0);
-    let csr = find_rating(bit_strs.clone(), false, 0);
+fn star2(bit_strs: &[Vec<u32>]) -> Answer {
+    let ogr = find_rating(bit_strs.to_vec(), true, 0);
+    let csr = find_rating(bit_strs.to_vec(), false, 0);
     
-    ogr * csr
+    (ogr * csr).into()
 }
 
 fn find_rating(bit_strs: Vec<Vec<u32>>, most_common: bool, i: usize) -> i128 {
@@ -70,7 +85,7 @@ fn find_rating(bit_strs: Vec<Vec<u32>>, most_common: bool, i: usize) -> i128 {
     }  
 }
 
-fn to_decimal(bit_str: &Vec<u32>) -> i128 {
+fn to_decimal(bit_str: &[u32]) -> i128 {
     let s: String = bit_str.iter()
         .map(|&i| char::from_digit(i, 2).unwrap())
         .collect();
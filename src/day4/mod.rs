@@ -1,17 +1,39 @@
+//! Day 4: Giant Squid
+
 use std::collections::HashSet;
 
 use itertools::Itertools;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+use crate::answer::Answer;
+use crate::common::scan;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = (Vec<u16>, Vec<u16>);
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 4, title: "Giant Squid", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<(Vec<u16>, Vec<u16>), SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, input: &(Vec<u16>, Vec<u16>)) -> Result<Answer, SolveError> {
+        star1(input)
+    }
+
+    fn part2(&self, input: &(Vec<u16>, Vec<u16>)) -> Result<Answer, SolveError> {
+        star2(input)
     }
 }
 
-fn star1(input: String) -> i128 {
-    let (draws, boards) = parse_input(&input);
+fn star1(input: &(Vec<u16>, Vec<u16>)) -> Result<Answer, SolveError> {
+    let (draws, boards) = input;
     let mut marked = vec![false; boards.len()];
 
     for &draw in draws.iter() {
@@ -21,34 +43,43 @@ fn star1(input: String) -> i128 {
             }
         }
         if let Some(board) = check(&marked) {
-            return score(&boards, &marked, board, draw);
+            return Ok(score(boards, &marked, board, draw).into());
         }
     }
 
-    panic!("Couldn't find winning board")
+    Err(SolveError::NoSolution("no board wins with these draws".to_string()))
 }
 
-fn parse_input(input: &str) -> (Vec<u16>, Vec<u16>) {
-    let input: Vec<_> = input.split_whitespace()
-        .collect();
+fn parse_input(input: &str) -> Result<(Vec<u16>, Vec<u16>), SolveError> {
+    let (draws, boards) = input.split_once("\n\n")
+        .ok_or_else(|| SolveError::at(input, input, "expected the draws, then the boards after a blank line"))?;
+    let draws = scan::separated(input, draws, ",")?;
+
+    let mut numbers = Vec::new();
+    for board in boards.split("\n\n") {
+        let rows: Vec<&str> = board.lines().collect();
+        if rows.len() != 5 {
+            return Err(SolveError::at(input, board, format!("expected a board of 5 rows, found {}", rows.len())));
+        }
 
-    let draws: Vec<u16> = input[0].split(',')
-        .map(|s| s.parse().unwrap())
-        .collect();
-    let boards: Vec<u16> = input[1..].iter()
-        .cloned()
-        .map(|s| s.parse().unwrap())
-        .collect();
+        for row in rows {
+            let row: Vec<u16> = scan::words(input, row)?;
+            if row.len() != 5 {
+                return Err(SolveError::at(input, board, format!("expected 5 numbers in a row, found {}", row.len())));
+            }
+            numbers.extend(row);
+        }
+    }
 
-    (draws, boards)
+    Ok((draws, numbers))
 }
 
-fn at<T>(boards: &Vec<T>, board: u16, row: u16, col: u16) -> &T {
+fn at<T>(boards: &[T], board: u16, row: u16, col: u16) -> &T {
     let i = (board * 25 + row * 5 + col) as usize;
     &boards[i]
 }
 
-fn check(marked: &Vec<bool>) -> Option<u16> {
+fn check(marked: &[bool]) -> Option<u16> {
     for board in 0..(marked.len() / 25) {
         let board = board as u16;
         for row in 0..5 {
@@ -68,8 +99,8 @@ fn check(marked: &Vec<bool>) -> Option<u16> {
 }
 
 fn score(
-    boards: &Vec<u16>, 
-    marked: &Vec<bool>, 
+    boards: &[u16], 
+    marked: &[bool], 
     board: u16, 
     winning_draw: u16
 ) -> i128 {
@@ -77,11 +108,11 @@ fn score(
         .filter(|(row, col)| !*at(marked, board, *row, *col))
         .map(|(row, col)| *at(boards, board, row, col))
         .sum();
-    (unmarked_sum * winning_draw) as i128
+    unmarked_sum as i128 * winning_draw as i128
 }
 
-fn star2(input: String) -> i128 {
-    let (draws, boards) = parse_input(&input);
+fn star2(input: &(Vec<u16>, Vec<u16>)) -> Result<Answer, SolveError> {
+    let (draws, boards) = input;
     let board_count = boards.len() / 25;
     let mut marked = vec![false; boards.len()];
     let mut winners = HashSet::new();
@@ -96,14 +127,14 @@ fn star2(input: String) -> i128 {
         winners = result.0;
         let last_winner = result.1;
         if winners.len() == board_count {
-            return score(&boards, &marked, last_winner, draw);
+            return Ok(score(boards, &marked, last_winner, draw).into());
         }
     }
 
-    panic!("Couldn't find winning board")
+    Err(SolveError::NoSolution("not every board wins with these draws".to_string()))
 }
 
-fn check_all(marked: &Vec<bool>, prev_winners: &HashSet<u16>) -> (HashSet<u16>, u16) {
+fn check_all(marked: &[bool], prev_winners: &HashSet<u16>) -> (HashSet<u16>, u16) {
     let mut winners = prev_winners.clone();
     let mut last_winner = 0;
     for board in 0..(marked.len() / 25) {
@@ -112,7 +143,6 @@ fn check_all(marked: &Vec<bool>, prev_winners: &HashSet<u16>) -> (HashSet<u16>,
             continue;
         }
 
-        let board = board as u16;
         for row in 0..5 {
             if (0..5).all(|col| *at(marked, board, row, col)) {
                 winners.insert(board);
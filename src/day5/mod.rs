@@ -1,34 +1,51 @@
+//! Day 5: Hydrothermal Venture
+
 use std::cmp;
 use std::collections::HashMap;
 use std::iter;
 
-use itertools::Itertools;
+use crate::answer::Answer;
+use crate::common::scan;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+type Line = ((i128, i128), (i128, i128));
+
+#[derive(Default)]
+pub struct Day5;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+impl Solution for Day5 {
+    type Input = Vec<Line>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 5, title: "Hydrothermal Venture", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<Line>, SolveError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, lines: &Vec<Line>) -> Result<Answer, SolveError> {
+        Ok(star1(lines))
     }
-}
 
-fn star1(input: String) -> i128 {
-    let lines: Vec<_> = input.split_whitespace()
-        .tuples::<(&str, &str, &str)>()
-        .map(|(a, _, b)| (v(a), v(b)))
-        .collect();
+    fn part2(&self, lines: &Vec<Line>) -> Result<Answer, SolveError> {
+        Ok(star2(lines))
+    }
+}
 
+fn star1(lines: &[Line]) -> Answer {
     let mut map: HashMap<(i128, i128), i128> = HashMap::new();
 
-    for ((x1, y1), (x2, y2)) in lines {
+    for &((x1, y1), (x2, y2)) in lines {
         let l = (cmp::max(i128::abs(x2 - x1), i128::abs(y2 - y1)) + 1) as usize;
         let positions: Vec<_> = if x1 == x2 {
-            let x = iter::repeat(x1).take(l);
+            let x = iter::repeat_n(x1, l);
             let y = num::range_step_inclusive(y1, y2, num::signum(y2 - y1));
             x.zip(y).collect()
         } else if y1 == y2 {
             let x = num::range_step_inclusive(x1, x2, num::signum(x2 - x1));
-            let y = iter::repeat(y1).take(l);
+            let y = iter::repeat_n(y1, l);
             x.zip(y).collect()
         } else {
             continue;
@@ -41,34 +58,27 @@ fn star1(input: String) -> i128 {
 
     map.values()
         .filter(|&&v| v >= 2)
-        .count() as i128
+        .count()
+        .into()
 }
 
-fn v(input: &str) -> (i128, i128) {
-    input.split(",")
-        .map(|s| s.parse::<i128>().unwrap())
-        .tuples::<(i128, i128)>()
-        .next()
-        .unwrap()
+fn parse_input(input: &str) -> Result<Vec<Line>, SolveError> {
+    let lines = scan::lines_matching(input, input, "{},{} -> {},{}")?;
+    Ok(lines.into_iter().map(|(x1, y1, x2, y2)| ((x1, y1), (x2, y2))).collect())
 }
 
-fn star2(input: String) -> i128 {
-    let lines: Vec<_> = input.split_whitespace()
-        .tuples::<(&str, &str, &str)>()
-        .map(|(a, _, b)| (v(a), v(b)))
-        .collect();
-
+fn star2(lines: &[Line]) -> Answer {
     let mut map: HashMap<(i128, i128), i128> = HashMap::new();
 
-    for ((x1, y1), (x2, y2)) in lines {
+    for &((x1, y1), (x2, y2)) in lines {
         let l = (cmp::max(i128::abs(x2 - x1), i128::abs(y2 - y1)) + 1) as usize;
         let positions: Vec<_> = if x1 == x2 {
-            let x = iter::repeat(x1).take(l);
+            let x = iter::repeat_n(x1, l);
             let y = num::range_step_inclusive(y1, y2, num::signum(y2 - y1));
             x.zip(y).collect()
         } else if y1 == y2 {
             let x = num::range_step_inclusive(x1, x2, num::signum(x2 - x1));
-            let y = iter::repeat(y1).take(l);
+            let y = iter::repeat_n(y1, l);
             x.zip(y).collect()
         } else {
             let x = num::range_step_inclusive(x1, x2, num::signum(x2 - x1));
@@ -83,5 +93,6 @@ fn star2(input: String) -> i128 {
 
     map.values()
         .filter(|&&v| v >= 2)
-        .count() as i128
+        .count()
+        .into()
 }
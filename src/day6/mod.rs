@@ -1,71 +1,71 @@
-use num::{BigInt, Zero};
+//! Day 6: Lanternfish
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
-    }
-}
+use num::BigInt;
 
-fn star1(input: String) -> i128 {
-    solve(&input, 80)
-}
+use crate::answer::Answer;
+use crate::common;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
 
-fn star2(input: String) -> i128 {
-    solve(&input, 256)
-}
+#[derive(Default)]
+pub struct Day6;
 
-fn solve(input: &str, max_gen: usize) -> i128 {
-    evolve(&parse_input(input), max_gen)
-}
+impl Solution for Day6 {
+    type Input = Vec<i128>;
 
-#[allow(dead_code)]
-fn solve_big(input: &str, max_gen: usize) -> BigInt {
-    evolve_big(&parse_input_big(&input), max_gen)
-}
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 6, title: "Lanternfish", unsolved: &[], input: InputMode::Normalized }
+    }
 
-fn parse_input(input: &str) -> Vec<i128> {
-    let input: Vec<i128> = input.split(",")
-        .map(|s| s.parse().unwrap())
-        .collect();
+    fn parse(&self, input: &str) -> Result<Vec<i128>, SolveError> {
+        parse_input(input)
+    }
 
-    let mut data = vec![0; 9];
+    fn part1(&self, data: &Vec<i128>) -> Result<Answer, SolveError> {
+        Ok(star1(data))
+    }
 
-    for &v in input.iter() {
-        data[v as usize] += 1;
+    fn part2(&self, data: &Vec<i128>) -> Result<Answer, SolveError> {
+        Ok(star2(data))
     }
+}
 
-    data
+fn star1(data: &[i128]) -> Answer {
+    evolve(data, 80).into()
 }
 
-#[allow(dead_code)]
-fn parse_input_big(input: &str) -> Vec<BigInt> {
-    let input: Vec<u8> = input.split(",")
-        .map(|s| s.parse().unwrap())
+fn star2(data: &[i128]) -> Answer {
+    let data: Vec<BigInt> = data.iter()
+        .map(|&v| BigInt::from(v))
         .collect();
 
-    let mut data = vec![Zero::zero(); 9];
+    evolve_big(&data, 256).into()
+}
+
+fn parse_input(input: &str) -> Result<Vec<i128>, SolveError> {
+    let mut data = vec![0; 9];
 
-    for &v in input.iter() {
-        let i = v as usize;
-        data[i] += 1;
+    for s in input.split(",") {
+        let v: usize = common::parse(input, s)?;
+        if v >= data.len() {
+            return Err(SolveError::at(input, s, format!("timer {} is out of range, expected 0 to 8", v)));
+        }
+        data[v] += 1;
     }
 
-    data
+    Ok(data)
 }
 
-fn evolve(data: &Vec<i128>, max_gen: usize) -> i128 {
-    let mut data = data.clone();
+fn evolve(data: &[i128], max_gen: usize) -> i128 {
+    let mut data = data.to_vec();
     for i in 0..max_gen {
         data[(i + 7) % 9] += data[i % 9];
     }
     data.iter().sum()
 }
 
-#[allow(dead_code)]
-fn evolve_big(data: &Vec<BigInt>, max_gen: usize) -> BigInt {
-    let mut data = data.clone();
+fn evolve_big(data: &[BigInt], max_gen: usize) -> BigInt {
+    let mut data = data.to_vec();
     for i in 0..max_gen {
         let new = data[i % 9].clone();
         data[(i + 7) % 9] += new;
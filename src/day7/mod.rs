@@ -1,24 +1,44 @@
+//! Day 7: The Treachery of Whales
+
 use std::cmp;
 
 use math::round;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+use crate::answer::Answer;
+use crate::common;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<i128>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 7, title: "The Treachery of Whales", unsolved: &[], input: InputMode::Normalized }
     }
-}
 
-fn star1(input: String) -> i128 {
-    binary_search(&input, score)
+    fn parse(&self, input: &str) -> Result<Vec<i128>, SolveError> {
+        input.split(",")
+            .map(|s| common::parse(input, s))
+            .collect()
+    }
+
+    fn part1(&self, positions: &Vec<i128>) -> Result<Answer, SolveError> {
+        Ok(star1(positions))
+    }
+
+    fn part2(&self, positions: &Vec<i128>) -> Result<Answer, SolveError> {
+        Ok(star2(positions))
+    }
 }
 
-fn binary_search(input: &str, score_fn: fn (&Vec<i128>, i128) -> i128) -> i128{
-    let positions: Vec<i128> = input.split(",")
-        .map(|s| s.parse().unwrap())
-        .collect();
+fn star1(positions: &[i128]) -> Answer {
+    binary_search(positions, score).into()
+}
 
+fn binary_search(positions: &[i128], score_fn: fn (&[i128], i128) -> i128) -> i128{
     let mut min = *positions.iter().min().unwrap();
     let mut max = *positions.iter().max().unwrap();
 
@@ -26,8 +46,8 @@ fn binary_search(input: &str, score_fn: fn (&Vec<i128>, i128) -> i128) -> i128{
         let pivot = min + div_up(max - min, 2);
         let a = min + div_down(pivot - min, 2);
         let b = pivot + div_up(max - pivot, 2);
-        let v_a = score_fn(&positions, a);
-        let v_b = score_fn(&positions, b);
+        let v_a = score_fn(positions, a);
+        let v_b = score_fn(positions, b);
 
         if v_a < v_b {
             max = pivot;
@@ -49,17 +69,17 @@ fn div_down(a: i128, b: i128) -> i128 {
     round::half_down(a as f64 / b as f64, 0) as i128
 }
 
-fn score(positions: &Vec<i128>, target: i128) -> i128 {
+fn score(positions: &[i128], target: i128) -> i128 {
     positions.iter()
         .map(|c| (c - target).abs())
         .sum()
 }
 
-fn star2(input: String) -> i128 {
-    estimate(&input, mean, score2)
+fn star2(positions: &[i128]) -> Answer {
+    estimate(positions, mean, score2).into()
 }
 
-fn score2(positions: &Vec<i128>, target: i128) -> i128 {
+fn score2(positions: &[i128], target: i128) -> i128 {
     positions.iter() 
         .map(|c| triangle((c - target).abs()))
         .sum()
@@ -69,10 +89,8 @@ fn triangle(n: i128) -> i128 {
     n * (n + 1) / 2
 }
 
-fn estimate(input: &str, estimator: fn (&mut Vec<i128>) -> i128, score_fn: fn (&Vec<i128>, i128) -> i128) -> i128{
-    let mut positions: Vec<i128> = input.split(",")
-        .map(|s| s.parse().unwrap())
-        .collect();
+fn estimate(positions: &[i128], estimator: fn (&mut [i128]) -> i128, score_fn: fn (&[i128], i128) -> i128) -> i128{
+    let mut positions = positions.to_vec();
 
     let m = estimator(&mut positions);
 
@@ -82,17 +100,17 @@ fn estimate(input: &str, estimator: fn (&mut Vec<i128>) -> i128, score_fn: fn (&
         .unwrap()
 }
 
-fn mean(positions: &mut Vec<i128>) -> i128 {
+fn mean(positions: &mut [i128]) -> i128 {
     let tot: i128 = positions.iter().sum();
     round::half_up(tot as f64 / positions.len() as f64, 0) as i128
 }
 
 #[allow(dead_code)]
-fn median(positions: &mut Vec<i128>) -> i128 {
+fn median(positions: &mut [i128]) -> i128 {
     positions.sort();
     let mid = positions.len() / 2;
-    if positions.len() % 2 == 0 {
-        mean(&mut vec![positions[mid - 1], positions[mid]]) as i128
+    if positions.len().is_multiple_of(2) {
+        mean(&mut [positions[mid - 1], positions[mid]])
     } else {
         positions[mid]
     }
@@ -1,31 +1,72 @@
+//! Day 8: Seven Segment Search
+
 use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+use crate::answer::Answer;
+use crate::common::scan;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Vec<String>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 8, title: "Seven Segment Search", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<String>, SolveError> {
+        input.split("\n")
+            .map(|line| {
+                let fields = scan::fields(input, line, "{} | {}")?;
+                for (field, count) in fields.into_iter().zip([10, 4]) {
+                    let patterns: Vec<&str> = field.split_whitespace().collect();
+                    if patterns.len() != count {
+                        return Err(SolveError::at(input, field, format!("expected {} patterns, found {}", count, patterns.len())));
+                    }
+                    if let Some(p) = patterns.iter().find(|p| p.len() > 7 || !p.chars().all(|c| ('a'..='g').contains(&c))) {
+                        return Err(SolveError::at(input, p, format!("expected segments `a` to `g`, got `{}`", p)));
+                    }
+                }
+
+                Ok(line.to_string())
+            })
+            .collect()
+    }
+
+    fn part1(&self, displays: &Vec<String>) -> Result<Answer, SolveError> {
+        Ok(star1(displays))
+    }
+
+    fn part2(&self, displays: &Vec<String>) -> Result<Answer, SolveError> {
+        star2(displays)
     }
 }
 
-fn star1(input: String) -> i128 {
-    input.split("\n")
+fn star1(displays: &[String]) -> Answer {
+    displays.iter()
         .flat_map(|line| line.split_whitespace().rev().take(4))
         .filter(|s| [2, 3, 4, 7].contains(&s.len()) )
-        .count() as i128
+        .count()
+        .into()
 }
 
-fn star2(input: String) -> i128 {
-    input.split("\n")
-        .map(|line| parse(line))
-        .sum()
+fn star2(displays: &[String]) -> Result<Answer, SolveError> {
+    displays.iter().enumerate()
+        .map(|(i, display)| parse(display).ok_or_else(|| {
+            SolveError::NoSolution(format!("the patterns on line {} don't fit any wiring", i + 1))
+        }))
+        .sum::<Result<i128, _>>()
+        .map(Answer::from)
 }
 
 #[allow(dead_code)]
-fn star2_2(input: String) -> i128 {
-    input.split("\n")
+fn star2_2(displays: &[String]) -> i128 {
+    displays.iter()
         .map(|line| {
             let (d, o) = line.split("|")
                 .tuples::<(&str, &str)>()
@@ -56,7 +97,9 @@ fn star2_2(input: String) -> i128 {
         .sum()
 }
 
-fn parse(display: &str) -> i128 {
+/// Works out the wiring from the ten patterns and decodes the output, if
+/// the patterns fit a wiring at all.
+fn parse(display: &str) -> Option<i128> {
     let signals: Vec<HashSet<_>> = display.split_whitespace()
         .take(10)
         .map(|s| s.chars().collect())
@@ -64,31 +107,30 @@ fn parse(display: &str) -> i128 {
 
     let mut digits: Vec<HashSet<char>> = vec![HashSet::new(); 10];
 
-    digits[1] = extract(&signals, |s| s.len() == 2);
-    digits[4] = extract(&signals, |s| s.len() == 4);
-    digits[7] = extract(&signals, |s| s.len() == 3);
-    digits[8] = extract(&signals, |s| s.len() == 7);
-    digits[3] = extract(&signals, |s| s.len() == 5 && digits[1].is_subset(s));
-    digits[9] = extract(&signals, |s| s.len() == 6 && digits[4].is_subset(s));
+    digits[1] = extract(&signals, |s| s.len() == 2)?;
+    digits[4] = extract(&signals, |s| s.len() == 4)?;
+    digits[7] = extract(&signals, |s| s.len() == 3)?;
+    digits[8] = extract(&signals, |s| s.len() == 7)?;
+    digits[3] = extract(&signals, |s| s.len() == 5 && digits[1].is_subset(s))?;
+    digits[9] = extract(&signals, |s| s.len() == 6 && digits[4].is_subset(s))?;
 
     let bl: HashSet<char> = digits[8].difference(&digits[9]).cloned().collect();
 
-    digits[2] = extract(&signals, |s| s.len() == 5 && bl.is_subset(s));
-    digits[5] = extract(&signals, |s| s.len() == 5 && !s.eq(&digits[2]) && !s.eq(&digits[3]));
+    digits[2] = extract(&signals, |s| s.len() == 5 && bl.is_subset(s))?;
+    digits[5] = extract(&signals, |s| s.len() == 5 && !s.eq(&digits[2]) && !s.eq(&digits[3]))?;
     digits[6] = digits[5].union(&bl).cloned().collect();
-    digits[0] = extract(&signals, |s| !digits.contains(s));
+    digits[0] = extract(&signals, |s| !digits.contains(s))?;
 
-    display.split_whitespace().rev().take(4)
+    let output = display.split_whitespace().rev().take(4)
         .map(|s| s.chars().collect::<HashSet<char>>())
-        .map(|s| digits.iter().position(|d| d.eq(&s)).unwrap())
-        .collect::<Vec<usize>>().iter().rev()
-        .fold(0, |acc, i| acc * 10 + i) as i128
+        .map(|s| digits.iter().position(|d| d.eq(&s)))
+        .collect::<Option<Vec<usize>>>()?;
+
+    Some(output.iter().rev().fold(0, |acc, i| acc * 10 + i) as i128)
 }
 
-fn extract<F>(signals: &Vec<HashSet<char>>, pred: F) -> HashSet<char> where F: Fn(&HashSet<char>) -> bool {
+fn extract<F>(signals: &[HashSet<char>], pred: F) -> Option<HashSet<char>> where F: Fn(&HashSet<char>) -> bool {
     signals.iter()
-        .filter(|s| pred(s))
-        .next()
-        .unwrap()
-        .clone()
+        .find(|s| pred(s))
+        .cloned()
 }
@@ -1,65 +1,65 @@
-use std::collections::{HashMap, HashSet};
+//! Day 9: Smoke Basin
 
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+use std::collections::HashSet;
+
+use crate::answer::Answer;
+use crate::common::grid::{Grid, Pos};
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Map;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 9, title: "Smoke Basin", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<Map, SolveError> {
+        parse_map(input)
+    }
+
+    fn part1(&self, map: &Map) -> Result<Answer, SolveError> {
+        Ok(star1(map))
     }
-}
 
-type Pos = (i128, i128);
-type Map = HashMap<Pos, u32>;
+    fn part2(&self, map: &Map) -> Result<Answer, SolveError> {
+        Ok(star2(map))
+    }
+}
 
-fn star1(input: String) -> i128 {
-    let map: Map = parse_map(&input);
+type Map = Grid<u32>;
 
-    map.keys()
-        .filter(|&pos| 
-            neighbours(&map, pos.clone()).iter()
-                .all(|n| map[pos] < map[n])
-        )
+fn star1(map: &Map) -> Answer {
+    low_points(map)
         .map(|pos| map[pos] as i128 + 1)
         .sum::<i128>()
+        .into()
 }
 
-fn parse_map(input: &str) -> Map {
-    input.split("\n").enumerate()
-        .flat_map(|(y, line)| 
-            line.chars()
-                .enumerate()
-                .map(move |(x, c)| ((x as i128, y as i128), c.to_digit(10).unwrap()))
-        )
-        .collect()
+fn parse_map(input: &str) -> Result<Map, SolveError> {
+    Grid::parse(input, "a digit", |c| c.to_digit(10))
 }
 
-fn neighbours(map: &Map, pos: Pos) -> HashSet<Pos> {
-    let (x, y) = pos;
-    [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].iter()
-        .filter(|pos| map.contains_key(pos))
-        .cloned()
-        .collect()
+fn low_points(map: &Map) -> impl Iterator<Item = Pos> + '_ {
+    map.positions()
+        .filter(|&pos| map.neighbours4(pos).all(|n| map[pos] < map[n]))
 }
 
-fn star2(input: String) -> i128 {
-    let map: Map = parse_map(&input);
-
-    let mut basin_sizes: Vec<usize> = map.keys()
-        .filter(|&pos| 
-            neighbours(&map, pos.clone()).iter()
-                .all(|n| map[pos] < map[n])
-        )
-        .map(|&low| {
+fn star2(map: &Map) -> Answer {
+    let mut basin_sizes: Vec<usize> = low_points(map)
+        .map(|low| {
             let mut open: HashSet<Pos> = HashSet::new();
             let mut closed: HashSet<Pos> = HashSet::new();
             open.insert(low);
-            while open.len() > 0 {
+            while !open.is_empty() {
                 let mut new_opened: HashSet<Pos> = HashSet::new();
                 let mut new_closed: HashSet<Pos> = HashSet::new();
                 for &pos in open.iter() {
-                    let n: HashSet<Pos> = neighbours(&map, pos.clone()).iter()
-                        .filter(|n| !closed.contains(n) && map[n] != 9)
-                        .cloned()
+                    let n: HashSet<Pos> = map.neighbours4(pos)
+                        .filter(|n| !closed.contains(n) && map[*n] != 9)
                         .collect();
 
                     new_closed.insert(pos);
@@ -75,5 +75,5 @@ fn star2(input: String) -> i128 {
         .collect();
 
     basin_sizes.sort();
-    basin_sizes.iter().rev().take(3).fold(1, |a, b| a * b) as i128
+    basin_sizes.iter().rev().take(3).product::<usize>().into()
 }
@@ -0,0 +1,103 @@
+//! Errors returned by solvers, located in the input when it's malformed.
+
+use std::error::Error;
+use std::fmt;
+
+/// Why a solver couldn't produce an answer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolveError {
+    /// The input is malformed
+    Parse(ParseError),
+    /// The input parsed, but the puzzle has no answer for it
+    NoSolution(String),
+}
+
+/// A malformed piece of input, located by 1-based line and column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    /// The offending line of input
+    pub snippet: String,
+    /// What was expected
+    pub message: String,
+}
+
+/// Longest snippet shown, longer lines are cut around the error.
+const SNIPPET_WIDTH: usize = 60;
+
+impl SolveError {
+    /// A parse error at `fragment`, which should be a slice of `input`,
+    /// as produced by splitting it. Fragments that aren't slices of the
+    /// input are looked up by their text instead.
+    pub fn at(input: &str, fragment: &str, message: impl Into<String>) -> SolveError {
+        let start = input.as_ptr() as usize;
+        let ptr = fragment.as_ptr() as usize;
+        let offset = if ptr >= start && ptr <= start + input.len() {
+            ptr - start
+        } else {
+            input.find(fragment).unwrap_or(0)
+        };
+
+        SolveError::at_offset(input, offset, message)
+    }
+
+    /// A parse error at a byte offset into `input`.
+    pub fn at_offset(input: &str, offset: usize, message: impl Into<String>) -> SolveError {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..].find('\n').map_or(input.len(), |i| offset + i);
+
+        SolveError::Parse(ParseError {
+            line: before.matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            snippet: input[line_start..line_end].trim_end_matches('\r').to_string(),
+            message: message.into(),
+        })
+    }
+
+    /// A one line description, without the snippet.
+    pub fn summary(&self) -> String {
+        match self {
+            SolveError::Parse(e) => format!("line {}, column {}: {}", e.line, e.column, e.message),
+            SolveError::NoSolution(message) => format!("no solution: {}", message),
+        }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.summary())?;
+        if let SolveError::Parse(e) = self {
+            let chars: Vec<char> = e.snippet.chars().collect();
+            let skip = (e.column - 1).saturating_sub(SNIPPET_WIDTH / 2).min(chars.len().saturating_sub(SNIPPET_WIDTH));
+            let shown: String = chars.iter().skip(skip).take(SNIPPET_WIDTH).collect();
+            let ellipsis = if skip > 0 { "..." } else { "" };
+
+            write!(f, "\n    {}{}", ellipsis, shown)?;
+            write!(f, "\n    {}{}^", " ".repeat(ellipsis.len()), " ".repeat(e.column - 1 - skip))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for SolveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let input = "12\n34\n5x6";
+        let fragment = input.split('\n').nth(2).unwrap();
+        let error = SolveError::at(input, &fragment[1..], "not a number");
+
+        assert_eq!(error, SolveError::Parse(ParseError {
+            line: 3, column: 2, snippet: "5x6".to_string(), message: "not a number".to_string(),
+        }));
+        assert_eq!(error.to_string(), "line 3, column 2: not a number\n    5x6\n     ^");
+    }
+}
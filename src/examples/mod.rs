@@ -0,0 +1,184 @@
+//! Puzzle examples with their expected answers, kept in `examples/dayNN`.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::answer::Answer;
+use crate::input::{Input, Source};
+use crate::runner;
+use crate::solution::Solver;
+use crate::verify::Verdict;
+
+/// An example input from the puzzle text, with the answers it should give.
+/// Examples live in `<root>/dayNN/<name>.txt`, and the expected answers in
+/// `<name>.answers` next to it, one `part answer` line per part. Examples
+/// that only apply to one part just leave the other part out, and a
+/// `# slow` line keeps `cargo test` from running the example by default.
+pub struct Example {
+    /// The file name without the extension
+    pub name: String,
+    /// The example file
+    pub path: PathBuf,
+    /// The example input
+    pub text: String,
+    /// Expected answers by part
+    pub expected: BTreeMap<u8, Answer>,
+}
+
+/// The directory holding a day's examples.
+pub fn dir(root: &Path, day: u8) -> PathBuf {
+    root.join(format!("day{:02}", day))
+}
+
+/// Loads every example for a day, sorted by name. A day without an example
+/// directory has no examples.
+pub fn load(root: &Path, day: u8) -> Result<Vec<Example>, Box<dyn Error>> {
+    let dir = dir(root, day);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("can't read example directory {}: {}", dir.display(), e))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "txt"));
+    paths.sort();
+
+    paths.into_iter().map(read).collect()
+}
+
+/// Reads an example and the answers next to it.
+pub fn read(path: PathBuf) -> Result<Example, Box<dyn Error>> {
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("can't read example file {}: {}", path.display(), e))?;
+    let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let expected = read_answers(&path.with_extension("answers"))?;
+
+    Ok(Example { name, path, text, expected })
+}
+
+/// The result of running one part against one example.
+pub struct Check {
+    /// The day
+    pub day: u8,
+    /// The part, 1 or 2
+    pub part: u8,
+    /// The example's name
+    pub name: String,
+    /// How long the part took
+    pub elapsed: Duration,
+    /// How the answer compares with the expected one
+    pub verdict: Verdict,
+}
+
+impl Check {
+    /// Whether the part failed or didn't give the expected answer.
+    pub fn failed(&self) -> bool {
+        self.verdict.failed()
+    }
+}
+
+/// Runs the parts of every example that have an expected answer. Asking
+/// for one `part` runs it against every example, failing those without
+/// an answer for it.
+pub fn check(solver: &'static dyn Solver, examples: Vec<Example>, part: Option<u8>, budget: Option<Duration>) -> Vec<Check> {
+    let day = solver.metadata().day;
+    let mut checks = Vec::new();
+    for example in examples {
+        for part_number in runner::parts(part) {
+            let expected = example.expected.get(&part_number);
+            if expected.is_none() && part.is_none() {
+                continue;
+            }
+
+            let input = Input { text: example.text.clone(), source: Source::File(example.path.clone()) };
+            let outcome = runner::quietly(|| runner::run_part(solver, part_number, Some(input), budget));
+            checks.push(Check {
+                day,
+                part: part_number,
+                name: example.name.clone(),
+                elapsed: outcome.elapsed,
+                verdict: Verdict::judge(outcome.status, expected),
+            });
+        }
+    }
+
+    checks
+}
+
+/// Prints a line per check.
+pub fn print_checks(checks: &[Check]) {
+    for c in checks {
+        println!("Day {} part {} {} ({:.2?}): {}", c.day, c.part, c.name, c.elapsed, c.verdict.describe());
+    }
+}
+
+/// Reads a `part answer` file. A missing file means no answers are known.
+pub fn read_answers(path: &Path) -> Result<BTreeMap<u8, Answer>, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("can't read answers file {}: {}", path.display(), e))?;
+
+    let mut answers = BTreeMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (part, answer) = line.split_once(' ')
+            .and_then(|(part, answer)| Some((part.parse().ok()?, answer.parse().ok()?)))
+            .filter(|(part, _)| [1, 2].contains(part))
+            .ok_or_else(|| format!("{}:{}: expected `part answer`, got `{}`", path.display(), i + 1, line))?;
+        answers.insert(part, answer);
+    }
+
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the parts of an example that have an expected answer. The
+    /// tests calling this are generated by the build script, one for each
+    /// example file.
+    fn check(day: u8, name: &str) {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        let example = read(dir(&root, day).join(format!("{}.txt", name))).unwrap();
+        let registry = crate::registry();
+        let solver = registry.get(day).unwrap();
+
+        for (&part, expected) in example.expected.iter() {
+            if solver.metadata().unsolved.contains(&part) {
+                continue;
+            }
+
+            let answer = solver.solve(part, &example.text).unwrap_or_else(|e| panic!("{}", e));
+            assert_eq!(&answer, expected, "day {} part {} {}", day, part, name);
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        let solver = crate::registry().get(1).unwrap();
+
+        let checks = super::check(solver, load(&root, 1).unwrap(), None, None);
+        assert_eq!(checks.iter().map(|c| c.part).collect::<Vec<_>>(), [1, 2]);
+        assert!(!checks.iter().any(Check::failed));
+
+        let mut example = read(dir(&root, 1).join("example.txt")).unwrap();
+        example.expected.remove(&2);
+        assert!(super::check(solver, vec![example], Some(2), None)[0].failed());
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));
+}
@@ -0,0 +1,259 @@
+//! Benchmark results recorded per commit, and comparisons between commits.
+
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::bench::{self, Measurement};
+
+/// One benchmarked part, as stored in the history file.
+#[derive(Serialize, Deserialize)]
+pub struct Record {
+    /// The commit that was benchmarked
+    pub commit: String,
+    /// Whether the working tree had uncommitted changes
+    pub dirty: bool,
+    /// When, in RFC 3339
+    pub timestamp: String,
+    /// Timed runs the measurement is over
+    pub runs: u32,
+    /// The part and its timings
+    #[serde(flatten)]
+    pub measurement: Measurement,
+}
+
+impl Record {
+    /// Median parse plus median solve time, which is what gets compared.
+    pub fn time_ns(&self) -> f64 {
+        self.measurement.parse.median_ns + self.measurement.solve.median_ns
+    }
+}
+
+/// Benchmark results of every run so far, one JSON record per line.
+pub struct History {
+    path: PathBuf,
+    /// Oldest first
+    pub records: Vec<Record>,
+}
+
+impl History {
+    /// Reads the history file. A missing file is an empty history.
+    pub fn load(path: &Path) -> Result<History, Box<dyn Error>> {
+        let mut history = History { path: path.to_path_buf(), records: Vec::new() };
+        if !path.exists() {
+            return Ok(history);
+        }
+
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("can't read bench history {}: {}", path.display(), e))?;
+        for (i, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let record = serde_json::from_str(line)
+                .map_err(|e| format!("{}:{}: {}", path.display(), i + 1, e))?;
+            history.records.push(record);
+        }
+
+        Ok(history)
+    }
+
+    /// Appends the measurements of a run made at the current commit.
+    pub fn append(&self, measurements: &[Measurement], runs: u32) -> Result<(), Box<dyn Error>> {
+        let (commit, dirty) = current_commit()?;
+        let timestamp = Utc::now().to_rfc3339();
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)
+            .map_err(|e| format!("can't open bench history {}: {}", self.path.display(), e))?;
+
+        for m in measurements {
+            let record = Record { commit: commit.clone(), dirty, timestamp: timestamp.clone(), runs, measurement: m.clone() };
+            writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// The full hash of the one recorded commit starting with the given
+    /// prefix. It's an error if none does, or if several do.
+    pub fn resolve(&self, prefix: &str) -> Result<&str, String> {
+        let mut commits: Vec<&str> = self.records.iter()
+            .map(|r| r.commit.as_str())
+            .filter(|commit| commit.starts_with(prefix))
+            .collect();
+        commits.sort_unstable();
+        commits.dedup();
+
+        match commits[..] {
+            [commit] => Ok(commit),
+            [] => Err(format!("no benchmarks recorded for commit {}", prefix)),
+            _ => Err(format!(
+                "commit prefix {} is ambiguous, it could be {}",
+                prefix, commits.iter().map(|c| short(c)).collect::<Vec<_>>().join(", "),
+            )),
+        }
+    }
+
+    /// The most recently recorded commit other than the given one.
+    pub fn previous(&self, commit: &str) -> Option<&str> {
+        self.records.iter().rev()
+            .map(|r| r.commit.as_str())
+            .find(|c| *c != commit)
+    }
+
+    /// The latest record of each part benchmarked at a commit.
+    pub fn latest(&self, commit: &str) -> Vec<&Record> {
+        let mut latest: Vec<&Record> = Vec::new();
+        for record in self.records.iter().filter(|r| r.commit == commit) {
+            let key = (record.measurement.day, record.measurement.part);
+            match latest.iter_mut().find(|r| (r.measurement.day, r.measurement.part) == key) {
+                Some(r) => *r = record,
+                None => latest.push(record),
+            }
+        }
+
+        latest.sort_by_key(|r| (r.measurement.day, r.measurement.part));
+        latest
+    }
+}
+
+/// The HEAD commit and whether the working tree is dirty.
+pub fn current_commit() -> Result<(String, bool), Box<dyn Error>> {
+    let git = |args: &[&str]| -> Result<String, Box<dyn Error>> {
+        let output = process::Command::new("git").args(args).output()
+            .map_err(|e| format!("can't run git: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()).into());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let commit = git(&["rev-parse", "HEAD"])?;
+    let dirty = !git(&["status", "--porcelain"])?.is_empty();
+
+    Ok((commit, dirty))
+}
+
+/// A part's time at a baseline commit and now.
+pub struct Comparison {
+    /// The day
+    pub day: u8,
+    /// The part, 1 or 2
+    pub part: u8,
+    /// Time at the baseline commit
+    pub baseline_ns: f64,
+    /// Time now
+    pub current_ns: f64,
+}
+
+impl Comparison {
+    /// Relative change in percent, positive when slower.
+    pub fn change(&self) -> f64 {
+        (self.current_ns - self.baseline_ns) / self.baseline_ns * 100.0
+    }
+
+    /// Whether the part got slower by more than `threshold` percent.
+    pub fn slower(&self, threshold: f64) -> bool {
+        self.change() > threshold
+    }
+}
+
+/// Pairs up the parts benchmarked at both commits.
+pub fn compare(baseline: &[&Record], current: &[&Record]) -> Vec<Comparison> {
+    current.iter()
+        .filter_map(|c| {
+            let (day, part) = (c.measurement.day, c.measurement.part);
+            let b = baseline.iter().find(|b| (b.measurement.day, b.measurement.part) == (day, part))?;
+            Some(Comparison { day, part, baseline_ns: b.time_ns(), current_ns: c.time_ns() })
+        })
+        .collect()
+}
+
+/// Prints a row per part, flagging those more than `threshold` percent slower.
+pub fn print_comparison(comparisons: &[Comparison], threshold: f64) {
+    println!("{:>3}  {:>4}  {:>12}  {:>12}  {:>8}", "Day", "Part", "Baseline", "Current", "Change");
+    for c in comparisons {
+        let flag = if c.slower(threshold) { "  SLOWER" } else { "" };
+        println!(
+            "{:>3}  {:>4}  {:>12}  {:>12}  {:>+7.1}%{}",
+            c.day, c.part, bench::format_ns(c.baseline_ns), bench::format_ns(c.current_ns), c.change(), flag,
+        );
+    }
+}
+
+/// The abbreviated form of a commit hash.
+pub fn short(commit: &str) -> &str {
+    &commit[..commit.len().min(10)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::Stats;
+
+    fn record(commit: &str, day: u8, part: u8, ns: f64) -> Record {
+        let stats = |median_ns| Stats { mean_ns: median_ns, median_ns, min_ns: median_ns, stddev_ns: 0.0 };
+        Record {
+            commit: commit.to_string(),
+            dirty: false,
+            timestamp: String::new(),
+            runs: 1,
+            measurement: Measurement { day, part, parse: stats(0.0), solve: stats(ns) },
+        }
+    }
+
+    fn history(records: Vec<Record>) -> History {
+        History { path: PathBuf::new(), records }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let history = history(vec![record("abc123", 1, 1, 1.0), record("abd456", 1, 1, 1.0), record("abc123", 1, 2, 1.0)]);
+        assert_eq!(history.resolve("abc"), Ok("abc123"));
+        assert!(history.resolve("ab").unwrap_err().contains("ambiguous"));
+        assert!(history.resolve("fff").unwrap_err().contains("no benchmarks"));
+    }
+
+    #[test]
+    fn test_previous_and_latest() {
+        let only = history(vec![record("aaa", 1, 1, 1.0)]);
+        assert_eq!(only.previous("aaa"), None);
+
+        let history = history(vec![
+            record("aaa", 1, 1, 100.0),
+            record("bbb", 1, 1, 300.0),
+            record("bbb", 1, 1, 200.0),
+            record("bbb", 2, 1, 50.0),
+        ]);
+        assert_eq!(history.previous("bbb"), Some("aaa"));
+
+        let latest = history.latest("bbb");
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].time_ns(), 200.0);
+    }
+
+    #[test]
+    fn test_compare() {
+        let history = history(vec![
+            record("aaa", 1, 1, 200.0),
+            record("aaa", 2, 1, 100.0),
+            record("bbb", 1, 1, 250.0),
+            record("bbb", 3, 1, 100.0),
+        ]);
+
+        // Only day 1 was benchmarked at both commits.
+        let comparisons = compare(&history.latest("aaa"), &history.latest("bbb"));
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!((comparisons[0].day, comparisons[0].part), (1, 1));
+
+        // A change exactly at the threshold isn't a slowdown.
+        assert_eq!(comparisons[0].change(), 25.0);
+        assert!(!comparisons[0].slower(25.0));
+        assert!(comparisons[0].slower(24.9));
+    }
+}
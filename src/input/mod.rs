@@ -0,0 +1,191 @@
+//! Finding and reading puzzle inputs.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Where a day's puzzle input was read from.
+#[derive(Clone, Debug)]
+pub enum Source {
+    /// A file given explicitly on the command line
+    File(PathBuf),
+    /// Standard input, given as `-` on the command line
+    Stdin,
+    /// A file in the local input directory
+    Local(PathBuf),
+    /// The file aoc-helper caches downloaded inputs in
+    AocHelper(PathBuf),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "file {}", path.display()),
+            Source::Stdin => write!(f, "stdin"),
+            Source::Local(path) => write!(f, "local {}", path.display()),
+            Source::AocHelper(path) => write!(f, "aoc-helper cache {}", path.display()),
+        }
+    }
+}
+
+impl Source {
+    /// The kind of source, without the path.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Source::File(_) => "file",
+            Source::Stdin => "stdin",
+            Source::Local(_) => "local",
+            Source::AocHelper(_) => "aoc-helper",
+        }
+    }
+
+    /// The file the input was read from, `None` for standard input.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Source::File(path) | Source::Local(path) | Source::AocHelper(path) => Some(path),
+            Source::Stdin => None,
+        }
+    }
+}
+
+/// The file name that stands for standard input.
+pub const STDIN: &str = "-";
+
+/// A puzzle input and where it came from.
+#[derive(Clone, Debug)]
+pub struct Input {
+    /// The contents
+    pub text: String,
+    /// Where the contents came from
+    pub source: Source,
+}
+
+/// Looks up puzzle inputs on disk, preferring `<root>/dayNN.txt` so that
+/// runs work offline, and only then looking at what aoc-helper downloaded.
+pub struct Inputs {
+    root: PathBuf,
+}
+
+impl Inputs {
+    /// Inputs kept in `root`.
+    pub fn new(root: &Path) -> Inputs {
+        Inputs { root: root.to_path_buf() }
+    }
+
+    /// Where a day's input is kept in `root`, whether it exists or not.
+    pub fn local_path(&self, day: u8) -> PathBuf {
+        self.root.join(format!("day{:02}.txt", day))
+    }
+
+    fn aoc_helper_path(day: u8) -> PathBuf {
+        PathBuf::from(format!("inputs/2021/day{}.txt", day))
+    }
+
+    /// Reads the input for a day from the given file, standard input if
+    /// the file is `-`, or from the local input directory. Returns `None`
+    /// if there is no local input, or the local file is still empty.
+    pub fn local(&self, day: u8, file: Option<&Path>) -> Result<Option<Input>, Box<dyn Error>> {
+        if file == Some(Path::new(STDIN)) {
+            return read_stdin().map(Some);
+        }
+
+        if let Some(file) = file {
+            return read(Source::File(file.to_path_buf())).map(Some);
+        }
+
+        let path = self.local_path(day);
+        if path.exists() && fs::metadata(&path)?.len() > 0 {
+            read(Source::Local(path)).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Like [`Inputs::local`], but falls back to the input aoc-helper has
+    /// already downloaded, without fetching anything.
+    pub fn load(&self, day: u8, file: Option<&Path>) -> Result<Input, Box<dyn Error>> {
+        if let Some(input) = self.local(day, file)? {
+            return Ok(input);
+        }
+
+        let cached = Inputs::aoc_helper_path(day);
+        if cached.exists() {
+            return read(Source::AocHelper(cached));
+        }
+
+        Err(format!(
+            "no input for day {}, expected {} or {}",
+            day,
+            self.local_path(day).display(),
+            cached.display(),
+        ).into())
+    }
+}
+
+/// Reads an input file as it is, solvers normalize it if they want to.
+fn read(source: Source) -> Result<Input, Box<dyn Error>> {
+    let path = match &source {
+        Source::File(path) | Source::Local(path) | Source::AocHelper(path) => path,
+        Source::Stdin => return read_stdin(),
+    };
+
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("can't read input file {}: {}", path.display(), e))?;
+
+    if text.trim().is_empty() {
+        return Err(format!("input file {} is empty", path.display()).into());
+    }
+
+    Ok(Input { text, source })
+}
+
+fn read_stdin() -> Result<Input, Box<dyn Error>> {
+    let mut text = String::new();
+    io::stdin().read_to_string(&mut text)
+        .map_err(|e| format!("can't read input from stdin: {}", e))?;
+
+    if text.trim().is_empty() {
+        return Err("input from stdin is empty".into());
+    }
+
+    Ok(Input { text, source: Source::Stdin })
+}
+
+/// Strips a byte order mark, turns CRLF line endings into LF, and removes
+/// trailing whitespace from every line along with any trailing blank
+/// lines, leaving no final newline. Borrows the input if it's already
+/// normalized.
+pub fn normalize(text: &str) -> Cow<'_, str> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let clean = !text.contains('\r')
+        && text.lines().all(|line| line.len() == line.trim_end().len())
+        && text.len() == text.trim_end().len();
+    if clean {
+        return Cow::Borrowed(text);
+    }
+
+    let mut normalized = String::with_capacity(text.len());
+    for line in text.lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    normalized.truncate(normalized.trim_end().len());
+
+    Cow::Owned(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert!(matches!(normalize("1,2\n3"), Cow::Borrowed("1,2\n3")));
+        assert!(matches!(normalize("\u{feff}1,2"), Cow::Borrowed("1,2")));
+        assert_eq!(normalize("\u{feff}a \r\n  b\t\r\n\r\n\n"), "a\n  b");
+        assert_eq!(normalize("a\n\nb\n"), "a\n\nb");
+    }
+}
@@ -0,0 +1,61 @@
+//! Solutions to Advent of Code 2021, and the tooling to run, test and
+//! benchmark them.
+//!
+//! Each day is a module holding a type that implements
+//! [`Solution`](solution::Solution): it parses the puzzle input once and
+//! solves both parts from the parsed input. [`registry`] has every day
+//! behind the object safe [`Solver`](solution::Solver) trait.
+//!
+//! ```
+//! use aoc2021_rs::day16::Day16;
+//! use aoc2021_rs::solution::Solution;
+//!
+//! let packet = Day16.parse("8A004A801A8002F478").unwrap();
+//! assert_eq!(packet.version_sum(), 16);
+//! ```
+
+#[macro_use] extern crate maplit;
+
+pub mod answer;
+pub mod batch;
+pub mod bench;
+pub mod common;
+pub mod error;
+pub mod examples;
+pub mod history;
+pub mod input;
+pub mod memory;
+pub mod runner;
+pub mod solution;
+pub mod verify;
+pub mod watch;
+
+use solution::register_days;
+
+register_days! {
+    day1::Day1,
+    day2::Day2,
+    day3::Day3,
+    day4::Day4,
+    day5::Day5,
+    day6::Day6,
+    day7::Day7,
+    day8::Day8,
+    day9::Day9,
+    day10::Day10,
+    day11::Day11,
+    day12::Day12,
+    day13::Day13,
+    day14::Day14,
+    day15::Day15,
+    day16::Day16,
+    day17::Day17,
+    day18::Day18,
+    day19::Day19,
+    day20::Day20,
+    day21::Day21,
+    day22::Day22,
+    day23::Day23,
+    day24::Day24,
+    day25::Day25,
+}
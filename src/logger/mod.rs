@@ -0,0 +1,102 @@
+use std::io::Write;
+
+use chrono::Local;
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Writes log records to stderr, keeping stdout for answers. Each day's
+/// solver logs under its own module, so records can be filtered by day.
+struct Logger {
+    default: LevelFilter,
+    /// Levels of the days named in the filter, as `dayN`
+    days: Vec<(String, LevelFilter)>,
+}
+
+impl Logger {
+    fn level(&self, target: &str) -> LevelFilter {
+        target.split("::")
+            .find_map(|segment| self.days.iter().find(|(day, _)| day == segment))
+            .map_or(self.default, |(_, level)| *level)
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let module = record.target().rsplit("::").next().unwrap_or("");
+        let _ = writeln!(
+            std::io::stderr(),
+            "{} {:<5} {}: {}",
+            Local::now().format("%H:%M:%S"), record.level(), module, record.args(),
+        );
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the logger. Each `-v` raises the default level from warn, and
+/// the filter overrides it. The filter is a comma separated list of
+/// levels, each either the default level or `dayN=level` for one day,
+/// like `info,day22=trace`.
+pub fn init(verbosity: u8, filter: Option<&str>) -> Result<(), String> {
+    let mut logger = Logger { default: verbosity_level(verbosity), days: Vec::new() };
+    if let Some(filter) = filter {
+        parse_filter(filter, &mut logger)?;
+    }
+
+    let max = logger.days.iter().map(|(_, level)| *level).fold(logger.default, Ord::max);
+    log::set_boxed_logger(Box::new(logger)).map_err(|e| e.to_string())?;
+    log::set_max_level(max);
+
+    Ok(())
+}
+
+fn verbosity_level(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+fn parse_filter(filter: &str, logger: &mut Logger) -> Result<(), String> {
+    for directive in filter.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        let level = |name: &str| name.parse::<LevelFilter>()
+            .map_err(|_| format!("unknown log level `{}` in `{}`", name, directive));
+
+        match directive.split_once('=') {
+            Some((day, name)) => {
+                let number: u8 = day.strip_prefix("day").and_then(|n| n.parse().ok())
+                    .ok_or_else(|| format!("expected `dayN=level`, got `{}`", directive))?;
+                logger.days.push((format!("day{}", number), level(name)?));
+            },
+            None => logger.default = level(directive)?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let mut logger = Logger { default: LevelFilter::Warn, days: Vec::new() };
+        parse_filter("info, day22=trace,day05=off", &mut logger).unwrap();
+
+        assert_eq!(logger.level("aoc2021_rs::day22"), LevelFilter::Trace);
+        assert_eq!(logger.level("aoc2021_rs::day5"), LevelFilter::Off);
+        assert_eq!(logger.level("aoc2021_rs::day2"), LevelFilter::Info);
+        assert!(parse_filter("day22=loud", &mut logger).is_err());
+        assert!(parse_filter("cave=info", &mut logger).is_err());
+    }
+}
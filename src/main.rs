@@ -1,88 +1,319 @@
-#[macro_use] extern crate maplit;
-
-mod common;
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-
-use std::env;
+mod cli;
+mod logger;
+mod scaffold;
+
+use std::error::Error;
 use std::fs;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
 
 use aoc_helper::{AocDay, Puzzle};
+use chrono::Local;
+use clap::Parser;
+
+use aoc2021_rs::answer::Answer;
+use aoc2021_rs::batch;
+use aoc2021_rs::bench::{self, Report};
+use aoc2021_rs::history::{self, History};
+use aoc2021_rs::input::Inputs;
+use aoc2021_rs::memory::{self, Counting};
+use aoc2021_rs::runner::{self, Status};
+use aoc2021_rs::solution::{Registry, Solver};
+use aoc2021_rs::verify::{self, KnownAnswers};
+use aoc2021_rs::watch::{Snapshot, Watcher};
+use aoc2021_rs::{examples, registry};
+
+use cli::{AllArgs, BatchArgs, BenchArgs, BenchCommand, Cli, Command, CompareArgs, Format, NewArgs, Selection, TestArgs, VerifyArgs, WatchArgs};
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(e) = logger::init(cli.verbose, cli.log.as_deref()) {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    if cli.allocs {
+        memory::enable();
+    }
+    let inputs = Inputs::new(&cli.input_dir);
+    let registry = registry();
+    let budget = cli.budget();
+
+    let result = match cli.command {
+        Command::Run(selection) => run(registry, &selection, &inputs, budget),
+        Command::Test(args) => test(registry, &args, budget),
+        Command::All(args) => run_all(registry, &args, &inputs, budget),
+        Command::Bench(args) => bench(registry, &args, &inputs, budget),
+        Command::Verify(args) => verify(registry, &args, &inputs, budget),
+        Command::List => list(registry),
+        Command::New(args) => new(&args, &inputs),
+        Command::Watch(args) => watch(registry, &args, &inputs, budget),
+        Command::Batch(args) => run_batch(registry, &args, budget),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(registry: &'static Registry, selection: &Selection, inputs: &Inputs, budget: Option<Duration>) -> Result<bool, Box<dyn Error>> {
+    let solver = lookup(registry, selection.day)?;
+    if selection.format != Format::Text {
+        return run_quietly(solver, selection, inputs, budget);
+    }
+
+    let input = inputs.local(selection.day, selection.input())?;
+    match &input {
+        Some(input) => eprintln!("Input: {}", input.source),
+        None => eprintln!(
+            "Input: no local file at {}, using aoc-helper",
+            inputs.local_path(selection.day).display(),
+        ),
+    }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let day_number = &args[1].parse::<u8>().unwrap();
-    let part_number = &args[2].parse::<u8>().unwrap();
-    let maybe_file = args.get(3);
+    let mut ok = true;
+    for part_number in runner::parts(selection.part) {
+        let outcome = runner::quietly(|| runner::run_part(solver, part_number, input.clone(), budget));
+        ok &= !outcome.failed();
+        match &outcome.status {
+            Status::Solved(answer) => {
+                let allocs = outcome.memory.map_or(String::new(), |m| format!(
+                    ", {} allocations, {} allocated, {} peak",
+                    m.allocations, memory::format_bytes(m.bytes), memory::format_bytes(m.peak_bytes),
+                ));
+                println!(
+                    "Day {} part {} (parse {:.2?}, solve {:.2?}{}): {}",
+                    selection.day, part_number, outcome.parse.unwrap_or_default(), outcome.solve.unwrap_or_default(), allocs, answer,
+                );
+            },
+            Status::NoInput => {
+                let puzzle = create_puzzle(solver, part_number);
+                let mut day = AocDay::new(2021, selection.day);
+                day.run(&puzzle)?;
+            },
+            Status::Error(e) => eprintln!("Day {} part {}: {}", selection.day, part_number, e),
+            status => eprintln!("Day {} part {}: {}", selection.day, part_number, status.describe()),
+        }
+    }
 
-    run(*day_number, *part_number, maybe_file);
+    Ok(ok)
 }
 
-fn run(day_number: u8, part_number: u8, maybe_file: Option<&String>) {
-    let mut puzzle = create_puzzle(day_number, part_number);
-    let mut day = AocDay::new(2021, day_number);
+/// Runs the selected parts without falling back to aoc-helper, which
+/// prints to stdout, and prints the results in a machine readable format.
+fn run_quietly(
+    solver: &'static dyn Solver, selection: &Selection, inputs: &Inputs, budget: Option<Duration>,
+) -> Result<bool, Box<dyn Error>> {
+    let input = match selection.input() {
+        Some(file) => Some(inputs.load(selection.day, Some(file))?),
+        None => inputs.load(selection.day, None).ok(),
+    };
+
+    let outcomes: Vec<_> = runner::quietly(|| runner::parts(selection.part).into_iter()
+        .map(|part| runner::run_part(solver, part, input.clone(), budget))
+        .collect());
+    print_outcomes(&outcomes, selection.format)?;
 
-    if let Some(file) = maybe_file {
-        let contents = fs::read_to_string(file)
-            .expect("Can't read test file!");
+    Ok(!outcomes.iter().any(|o| o.failed()))
+}
+
+fn print_outcomes(outcomes: &[runner::Outcome], format: Format) -> Result<(), Box<dyn Error>> {
+    match format {
+        Format::Text => runner::print_table(outcomes),
+        Format::Json => runner::print_json(outcomes)?,
+        Format::Csv => runner::print_csv(outcomes),
+    }
 
-        puzzle.examples(&[contents]);
+    Ok(())
+}
+
+fn test(registry: &'static Registry, args: &TestArgs, budget: Option<Duration>) -> Result<bool, Box<dyn Error>> {
+    let solver = lookup(registry, args.day)?;
+    if args.inputs.is_empty() {
+        return test_examples(solver, args, budget);
+    }
+
+    let examples = args.inputs.iter()
+        .map(|file| fs::read_to_string(file)
+            .map_err(|e| format!("can't read example file {}: {}", file.display(), e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let day = AocDay::new(2021, args.day);
+    for part_number in runner::parts(args.part) {
+        let mut puzzle = create_puzzle(solver, part_number);
+        puzzle.examples(&examples);
         day.test(&puzzle);
-    } else {
-        day.run(&puzzle).expect("Failed to run!");
-    }
-}
-
-fn create_puzzle(day_number: u8, part_number: u8) -> Puzzle<String, i128> {
-    match day_number {
-        1 => Puzzle::new(part_number, day1::solver(part_number)),
-        2 => Puzzle::new(part_number, day2::solver(part_number)),
-        3 => Puzzle::new(part_number, day3::solver(part_number)),
-        4 => Puzzle::new(part_number, day4::solver(part_number)),
-        5 => Puzzle::new(part_number, day5::solver(part_number)),
-        6 => Puzzle::new(part_number, day6::solver(part_number)),
-        7 => Puzzle::new(part_number, day7::solver(part_number)),
-        8 => Puzzle::new(part_number, day8::solver(part_number)),
-        9 => Puzzle::new(part_number, day9::solver(part_number)),
-        10 => Puzzle::new(part_number, day10::solver(part_number)),
-        11 => Puzzle::new(part_number, day11::solver(part_number)),
-        12 => Puzzle::new(part_number, day12::solver(part_number)),
-        13 => Puzzle::new(part_number, day13::solver(part_number)),
-        14 => Puzzle::new(part_number, day14::solver(part_number)),
-        15 => Puzzle::new(part_number, day15::solver(part_number)),
-        16 => Puzzle::new(part_number, day16::solver(part_number)),
-        17 => Puzzle::new(part_number, day17::solver(part_number)),
-        18 => Puzzle::new(part_number, day18::solver(part_number)),
-        19 => Puzzle::new(part_number, day19::solver(part_number)),
-        20 => Puzzle::new(part_number, day20::solver(part_number)),
-        21 => Puzzle::new(part_number, day21::solver(part_number)),
-        22 => Puzzle::new(part_number, day22::solver(part_number)),
-        23 => Puzzle::new(part_number, day23::solver(part_number)),
-        24 => Puzzle::new(part_number, day24::solver(part_number)),
-        25 => Puzzle::new(part_number, day25::solver(part_number)),
-        _ => panic!("Unknown day!"),
     }
+
+    Ok(true)
+}
+
+fn test_examples(solver: &'static dyn Solver, args: &TestArgs, budget: Option<Duration>) -> Result<bool, Box<dyn Error>> {
+    let examples = examples::load(&args.examples_dir, args.day)?;
+    if examples.is_empty() {
+        return Err(format!("no examples in {}", examples::dir(&args.examples_dir, args.day).display()).into());
+    }
+
+    let checks = examples::check(solver, examples, args.part, budget);
+    examples::print_checks(&checks);
+
+    Ok(!checks.iter().any(|c| c.failed()))
+}
+
+fn run_all(registry: &'static Registry, args: &AllArgs, inputs: &Inputs, budget: Option<Duration>) -> Result<bool, Box<dyn Error>> {
+    let outcomes = runner::run_all(registry, |_| true, |day| inputs.load(day, None).ok(), budget);
+    print_outcomes(&outcomes, args.format)?;
+
+    Ok(!outcomes.iter().any(|o| o.failed()))
+}
+
+fn bench(registry: &'static Registry, args: &BenchArgs, inputs: &Inputs, budget: Option<Duration>) -> Result<bool, Box<dyn Error>> {
+    if let Some(BenchCommand::Compare(compare)) = &args.command {
+        return bench_compare(compare);
+    }
+
+    let args = &args.run;
+    let input = |day| match inputs.load(day, args.input.as_deref()) {
+        Ok(input) => Ok(Some(input)),
+        Err(e) if args.day.is_none() => {
+            eprintln!("Skipping day {}: {}", day, e);
+            Ok(None)
+        },
+        Err(e) => Err(e),
+    };
+    let filter = |day| args.day.is_none_or(|d| d == day);
+    let run = bench::run_all(registry, filter, input, &runner::parts(args.part), args.warmup, args.runs, budget)?;
+    for (day, part, message) in &run.failures {
+        eprintln!("Day {} part {} FAILED: {}", day, part, message);
+    }
+
+    bench::print_table(&run.measurements);
+
+    if !args.no_history {
+        History::load(&args.history)?.append(&run.measurements, args.runs)?;
+    }
+
+    if let Some(path) = &args.json {
+        let report = Report { warmup: args.warmup, runs: args.runs, measurements: run.measurements };
+        report.save(path)?;
+        eprintln!("Wrote results to {}", path.display());
+    }
+
+    Ok(run.failures.is_empty())
+}
+
+fn bench_compare(args: &CompareArgs) -> Result<bool, Box<dyn Error>> {
+    let history = History::load(&args.history)?;
+    let current = match &args.commit {
+        Some(prefix) => history.resolve(prefix)?.to_string(),
+        None => history::current_commit()?.0,
+    };
+    let baseline = match &args.baseline {
+        Some(prefix) => history.resolve(prefix)?,
+        None => history.previous(&current).ok_or("no earlier benchmarked commit to compare against")?,
+    };
+
+    let comparisons = history::compare(&history.latest(baseline), &history.latest(&current));
+    if comparisons.is_empty() {
+        return Err(format!(
+            "no parts benchmarked at both {} and {}", history::short(baseline), history::short(&current),
+        ).into());
+    }
+
+    println!("Comparing {} against baseline {}", history::short(&current), history::short(baseline));
+    history::print_comparison(&comparisons, args.threshold);
+
+    Ok(!comparisons.iter().any(|c| c.slower(args.threshold)))
+}
+
+fn verify(registry: &'static Registry, args: &VerifyArgs, inputs: &Inputs, budget: Option<Duration>) -> Result<bool, Box<dyn Error>> {
+    let mut known = KnownAnswers::load(&args.answers)?;
+    let outcomes = runner::run_all(
+        registry,
+        |day| args.day.is_none_or(|d| d == day),
+        |day| inputs.load(day, None).ok(),
+        budget,
+    );
+    let mut checks = verify::check(outcomes, &known);
+    verify::print_report(&checks);
+
+    if args.record {
+        known.record(&mut checks);
+        known.save()?;
+        eprintln!("Recorded missing answers in {}", args.answers.display());
+    }
+
+    Ok(verify::passed(&checks))
+}
+
+fn list(registry: &Registry) -> Result<bool, Box<dyn Error>> {
+    for solver in registry.iter() {
+        let metadata = solver.metadata();
+        let parts: Vec<String> = [1, 2].iter()
+            .filter(|part| !metadata.unsolved.contains(part))
+            .map(|part| part.to_string())
+            .collect();
+
+        let parts = if parts.is_empty() { "none".to_string() } else { parts.join(", ") };
+        println!("{:>3}  {:<24}  solved parts: {}", metadata.day, metadata.title, parts);
+    }
+
+    Ok(true)
+}
+
+fn new(args: &NewArgs, inputs: &Inputs) -> Result<bool, Box<dyn Error>> {
+    let created = scaffold::new_day(args.day, &args.title, &inputs.local_path(args.day), &args.examples_dir)?;
+    for path in created {
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(true)
+}
+
+fn watch(registry: &'static Registry, args: &WatchArgs, inputs: &Inputs, budget: Option<Duration>) -> Result<bool, Box<dyn Error>> {
+    let solver = lookup(registry, args.day)?;
+    let input = args.input.clone().unwrap_or_else(|| inputs.local_path(args.day));
+    let example_dir = examples::dir(&args.examples_dir, args.day);
+    eprintln!("Watching {} and {}, press Ctrl-C to stop", input.display(), example_dir.display());
+
+    let mut watcher = Watcher::new(solver, runner::parts(args.part), budget);
+    let mut last = None;
+    loop {
+        let snapshot = Snapshot::take(&[&input], &[&example_dir]);
+        if last.as_ref() != Some(&snapshot) {
+            if last.is_some() {
+                println!();
+            }
+            println!("[{}] Day {}", Local::now().format("%H:%M:%S"), args.day);
+            watcher.round(inputs, args.input.as_deref(), &args.examples_dir);
+            last = Some(snapshot);
+        }
+
+        thread::sleep(Duration::from_millis(args.interval));
+    }
+}
+
+fn run_batch(registry: &'static Registry, args: &BatchArgs, budget: Option<Duration>) -> Result<bool, Box<dyn Error>> {
+    let solver = lookup(registry, args.day)?;
+    let files = batch::files(&args.inputs)?;
+    let rows = batch::run(solver, &runner::parts(args.part), &files, budget)?;
+    batch::print_table(&rows);
+
+    Ok(!rows.iter().any(|r| r.failed()))
+}
+
+fn lookup(registry: &'static Registry, day: u8) -> Result<&'static dyn Solver, Box<dyn Error>> {
+    registry.get(day).ok_or_else(|| format!("day {} is not implemented", day).into())
+}
+
+fn create_puzzle(solver: &dyn Solver, part_number: u8) -> Puzzle<String, Answer> {
+    Puzzle::new(part_number, solver.function(part_number))
 }
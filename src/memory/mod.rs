@@ -0,0 +1,152 @@
+//! Counting allocations, to report how much memory each part uses.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Allocations made while running a part.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct AllocStats {
+    /// Number of allocations, reallocations included
+    pub allocations: u64,
+    /// Bytes allocated in total
+    pub bytes: u64,
+    /// Most bytes live at once, beyond what was live when the part started
+    pub peak_bytes: u64,
+}
+
+#[derive(Clone, Copy)]
+struct Counts {
+    allocations: u64,
+    bytes: u64,
+    live: i64,
+    peak: i64,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Counted per thread, so that parts left running after their time
+    // budget don't count towards the next part.
+    static COUNTS: Cell<Counts> = const { Cell::new(Counts { allocations: 0, bytes: 0, live: 0, peak: 0 }) };
+}
+
+/// A global allocator that counts allocations once [`enable`] has been
+/// called, and otherwise just passes them on to the system allocator.
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(1, layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(1, layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, -(layout.size() as i64));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(1, new_size as i64 - layout.size() as i64);
+        }
+        new_ptr
+    }
+}
+
+fn record(allocations: u64, change: i64) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let _ = COUNTS.try_with(|counts| {
+        let mut c = counts.get();
+        c.allocations += allocations;
+        c.bytes += change.max(0) as u64;
+        c.live += change;
+        c.peak = c.peak.max(c.live);
+        counts.set(c);
+    });
+}
+
+/// Starts counting. Counts are only meaningful if [`Counting`] is the
+/// global allocator.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Runs `f`, counting the allocations it makes on this thread if
+/// counting is enabled.
+pub fn track<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return (f(), None);
+    }
+
+    let before = COUNTS.with(|counts| {
+        let mut c = counts.get();
+        c.peak = c.live;
+        counts.set(c);
+        c
+    });
+    let result = f();
+    let after = COUNTS.with(Cell::get);
+
+    let stats = AllocStats {
+        allocations: after.allocations - before.allocations,
+        bytes: after.bytes - before.bytes,
+        peak_bytes: (after.peak - before.live).max(0) as u64,
+    };
+    (result, Some(stats))
+}
+
+/// Formats a byte count with a binary unit.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", value, UNITS[unit]) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    fn test_track() {
+        enable();
+        let (_, stats) = track(|| {
+            let small = vec![0_u8; 1000];
+            drop(small);
+            vec![0_u8; 4000].len()
+        });
+        let stats = stats.unwrap();
+
+        assert_eq!(stats.allocations, 2);
+        assert_eq!(stats.bytes, 5000);
+        assert_eq!(stats.peak_bytes, 4000);
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+    }
+}
@@ -0,0 +1,385 @@
+//! Running parts in isolation, under a time budget.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::input::{Input, Source};
+use crate::memory::{self, AllocStats};
+use crate::solution::{Registry, Solver};
+
+/// How running a part went.
+pub enum Status {
+    /// The solver found an answer
+    Solved(Answer),
+    /// The solver returned an error
+    Error(SolveError),
+    /// The solver panicked
+    Panicked(String),
+    /// The solver ran over its time budget
+    TimedOut(Duration),
+    /// There's no input to run the part on
+    NoInput,
+    /// The day isn't registered, or the part is unsolved
+    Unimplemented,
+}
+
+impl Status {
+    /// The answer or what went wrong, on one line.
+    pub fn describe(&self) -> String {
+        match self {
+            Status::Solved(answer) => answer.serialize(),
+            Status::Error(e) => format!("ERROR: {}", e.summary()),
+            Status::Panicked(message) => format!("panicked: {}", message),
+            Status::TimedOut(budget) => format!("timed out after {:.2?}", budget),
+            Status::NoInput => "no input".to_string(),
+            Status::Unimplemented => "unimplemented".to_string(),
+        }
+    }
+}
+
+/// The result of running one part.
+pub struct Outcome {
+    /// The day
+    pub day: u8,
+    /// The part, 1 or 2
+    pub part: u8,
+    /// The answer, or why there isn't one
+    pub status: Status,
+    /// Where the input came from, if there was one
+    pub source: Option<Source>,
+    /// Wall time of the whole run
+    pub elapsed: Duration,
+    /// How long parsing took, once the part has an answer
+    pub parse: Option<Duration>,
+    /// How long solving took, once the part has an answer
+    pub solve: Option<Duration>,
+    /// Allocations made by the part, if they are being counted
+    pub memory: Option<AllocStats>,
+}
+
+impl Outcome {
+    /// Whether the solver errored, panicked or timed out.
+    pub fn failed(&self) -> bool {
+        matches!(self.status, Status::Error(_) | Status::Panicked(_) | Status::TimedOut(_))
+    }
+}
+
+/// Why a solver run on a worker thread didn't return.
+pub enum Interrupted {
+    /// The solver panicked, with the panic message
+    Panicked(String),
+    /// The solver ran over the budget
+    TimedOut(Duration),
+}
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Interrupted::Panicked(message) => write!(f, "panicked: {}", message),
+            Interrupted::TimedOut(budget) => write!(f, "timed out after {:.2?}", budget),
+        }
+    }
+}
+
+/// Solvers recurse deeply on some inputs, so workers get more than the
+/// default 2 MiB of stack.
+const WORKER_STACK: usize = 64 * 1024 * 1024;
+
+/// Runs `f` on a worker thread, catching panics and giving up once it has
+/// run for longer than the budget, if there is one. A thread can't be
+/// killed, so a worker that runs over budget is left running in the
+/// background until the program exits.
+pub fn isolate<T, F>(budget: Option<Duration>, f: F) -> Result<T, Interrupted>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name("solver".to_string())
+        .stack_size(WORKER_STACK)
+        .spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(f));
+            let _ = sender.send(result.map_err(|payload| panic_message(payload.as_ref())));
+        })
+        .map_err(|e| Interrupted::Panicked(format!("can't start a worker thread: {}", e)))?;
+
+    let result = match budget {
+        Some(budget) => receiver.recv_timeout(budget).map_err(|e| match e {
+            RecvTimeoutError::Timeout => Interrupted::TimedOut(budget),
+            RecvTimeoutError::Disconnected => Interrupted::Panicked("worker thread died".to_string()),
+        })?,
+        None => receiver.recv().map_err(|_| Interrupted::Panicked("worker thread died".to_string()))?,
+    };
+    result.map_err(Interrupted::Panicked)
+}
+
+/// The parts to run: the one asked for, or both.
+pub fn parts(part: Option<u8>) -> Vec<u8> {
+    match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    }
+}
+
+/// Runs a single part on a worker thread under the time budget, so that
+/// the remaining parts can still be run if it panics or hangs. Parts the
+/// solution marks as unsolved are skipped.
+pub fn run_part(solver: &'static dyn Solver, part: u8, input: Option<Input>, budget: Option<Duration>) -> Outcome {
+    let metadata = solver.metadata();
+    let mut outcome = Outcome {
+        day: metadata.day,
+        part,
+        status: Status::Unimplemented,
+        source: None,
+        elapsed: Duration::ZERO,
+        parse: None,
+        solve: None,
+        memory: None,
+    };
+
+    if metadata.unsolved.contains(&part) {
+        return outcome;
+    }
+
+    let input = match input {
+        Some(input) => input,
+        None => {
+            outcome.status = Status::NoInput;
+            return outcome;
+        }
+    };
+
+    outcome.source = Some(input.source);
+    let text = input.text;
+    let start = Instant::now();
+    let result = isolate(budget, move || memory::track(|| solver.solve_timed(part, &text)));
+    outcome.elapsed = start.elapsed();
+    outcome.status = match result {
+        Ok((result, stats)) => {
+            outcome.memory = stats;
+            match result {
+                Ok(timed) => {
+                    outcome.parse = Some(timed.parse);
+                    outcome.solve = Some(timed.solve);
+                    Status::Solved(timed.answer)
+                },
+                Err(e) => Status::Error(e),
+            }
+        },
+        Err(Interrupted::Panicked(message)) => Status::Panicked(message),
+        Err(Interrupted::TimedOut(budget)) => Status::TimedOut(budget),
+    };
+
+    outcome
+}
+
+/// Runs every part of the registered days matching the filter, without
+/// the default panic output cluttering the table.
+pub fn run_all<F, I>(registry: &'static Registry, filter: F, input: I, budget: Option<Duration>) -> Vec<Outcome>
+where
+    F: Fn(u8) -> bool,
+    I: Fn(u8) -> Option<Input>,
+{
+    quietly(|| {
+        let mut outcomes = Vec::new();
+        for solver in registry.iter().filter(|s| filter(s.metadata().day)) {
+            let day_input = input(solver.metadata().day);
+            for part in 1..=2 {
+                outcomes.push(run_part(solver, part, day_input.clone(), budget));
+            }
+        }
+
+        outcomes
+    })
+}
+
+/// Runs `f` without the default panic output, for callers that catch
+/// panics and report them themselves.
+pub fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = f();
+    panic::set_hook(hook);
+    result
+}
+
+/// The message of a panic payload, if it has one.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Prints one row per part. Multi-line answers continue on the following
+/// lines, in the answer column.
+pub fn print_table(outcomes: &[Outcome]) {
+    let cells: Vec<Vec<String>> = outcomes.iter()
+        .map(|o| match &o.status {
+            Status::Solved(answer) => answer.lines(),
+            status => vec![status.describe()],
+        })
+        .collect();
+
+    let width = cells.iter()
+        .flatten()
+        .map(|c| c.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Answer".len());
+
+    let counted = outcomes.iter().any(|o| o.memory.is_some());
+    let memory_header = if counted { format!("  {:>10}  {:>10}  {:>10}", "Allocs", "Allocated", "Peak") } else { String::new() };
+    println!("{:>3}  {:>4}  {:<width$}  {:>12}{}  Input", "Day", "Part", "Answer", "Time", memory_header, width = width);
+    for (o, lines) in outcomes.iter().zip(cells.iter()) {
+        let time = match o.status {
+            Status::Solved(_) | Status::Error(_) | Status::Panicked(_) | Status::TimedOut(_) => format!("{:.2?}", o.elapsed),
+            _ => "-".to_string(),
+        };
+        let memory = match (&o.memory, counted) {
+            (Some(m), _) => format!(
+                "  {:>10}  {:>10}  {:>10}", m.allocations, memory::format_bytes(m.bytes), memory::format_bytes(m.peak_bytes),
+            ),
+            (None, true) => format!("  {:>10}  {:>10}  {:>10}", "-", "-", "-"),
+            (None, false) => String::new(),
+        };
+        let source = o.source.as_ref().map_or("-", |s| s.label());
+        println!("{:>3}  {:>4}  {:<width$}  {:>12}{}  {}", o.day, o.part, lines[0], time, memory, source, width = width);
+        for line in lines.iter().skip(1) {
+            println!("{:>3}  {:>4}  {}", "", "", line);
+        }
+    }
+
+    let total: Duration = outcomes.iter().map(|o| o.elapsed).sum();
+    let solved = outcomes.iter().filter(|o| matches!(o.status, Status::Solved(_))).count();
+    println!(
+        "{:<11}  {:<width$}  {:>12}",
+        "Total", format!("{} of {} solved", solved, outcomes.len()), format!("{:.2?}", total), width = width,
+    );
+}
+
+/// One part's outcome, as written by the machine readable formats.
+#[derive(Serialize)]
+pub struct Record {
+    /// The day
+    pub day: u8,
+    /// The part, 1 or 2
+    pub part: u8,
+    /// One of `solved`, `error`, `panicked`, `timed_out`, `no_input` and
+    /// `unimplemented`
+    pub status: &'static str,
+    /// The answer, with the rows of ASCII art separated by newlines
+    pub answer: Option<String>,
+    /// Why the part failed
+    pub message: Option<String>,
+    /// Wall time of the run in nanoseconds
+    pub elapsed_ns: u64,
+    /// The kind of input, as in the table's input column
+    pub source: Option<&'static str>,
+    /// The input file, if it was read from one
+    pub path: Option<PathBuf>,
+    /// Allocations made by the part, if they were counted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<AllocStats>,
+}
+
+impl From<&Outcome> for Record {
+    fn from(o: &Outcome) -> Record {
+        let (status, answer, message) = match &o.status {
+            Status::Solved(answer) => ("solved", Some(answer.lines().join("\n")), None),
+            Status::Error(e) => ("error", None, Some(e.summary())),
+            Status::Panicked(message) => ("panicked", None, Some(message.clone())),
+            Status::TimedOut(budget) => ("timed_out", None, Some(format!("timed out after {:.2?}", budget))),
+            Status::NoInput => ("no_input", None, None),
+            Status::Unimplemented => ("unimplemented", None, None),
+        };
+
+        Record {
+            day: o.day,
+            part: o.part,
+            status,
+            answer,
+            message,
+            elapsed_ns: o.elapsed.as_nanos() as u64,
+            source: o.source.as_ref().map(|s| s.label()),
+            path: o.source.as_ref().and_then(|s| s.path()).map(|p| p.to_path_buf()),
+            memory: o.memory,
+        }
+    }
+}
+
+/// Prints the outcomes as a JSON array of [`Record`]s.
+pub fn print_json(outcomes: &[Outcome]) -> serde_json::Result<()> {
+    let records: Vec<Record> = outcomes.iter().map(Record::from).collect();
+    println!("{}", serde_json::to_string_pretty(&records)?);
+    Ok(())
+}
+
+/// Prints the outcomes as CSV, one [`Record`] per row after a header.
+pub fn print_csv(outcomes: &[Outcome]) {
+    println!("day,part,status,answer,message,elapsed_ns,source,path,allocations,bytes,peak_bytes");
+    for r in outcomes.iter().map(Record::from) {
+        let fields = [
+            r.day.to_string(),
+            r.part.to_string(),
+            r.status.to_string(),
+            r.answer.unwrap_or_default(),
+            r.message.unwrap_or_default(),
+            r.elapsed_ns.to_string(),
+            r.source.unwrap_or_default().to_string(),
+            r.path.map(|p| p.display().to_string()).unwrap_or_default(),
+            r.memory.map(|m| m.allocations.to_string()).unwrap_or_default(),
+            r.memory.map(|m| m.bytes.to_string()).unwrap_or_default(),
+            r.memory.map(|m| m.peak_bytes.to_string()).unwrap_or_default(),
+        ];
+        println!("{}", fields.iter().map(|f| csv_field(f)).join(","));
+    }
+}
+
+/// Quotes a CSV field if it needs it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isolate() {
+        let budget = Some(Duration::from_millis(50));
+        assert!(matches!(isolate(budget, || 42), Ok(42)));
+        assert!(matches!(
+            quietly(|| isolate(budget, || -> u8 { panic!("boom") })),
+            Err(Interrupted::Panicked(message)) if message == "boom"
+        ));
+        assert!(matches!(
+            isolate(budget, || thread::sleep(Duration::from_secs(1))),
+            Err(Interrupted::TimedOut(_))
+        ));
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("12521"), "12521");
+        assert_eq!(csv_field("line 1, column 2: can't parse `\"x\"`"), "\"line 1, column 2: can't parse `\"\"x\"\"`\"");
+        assert_eq!(csv_field("#.#\n.#."), "\"#.#\n.#.\"");
+    }
+}
@@ -0,0 +1,119 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aoc2021_rs::examples;
+
+const TEMPLATE: &str = "src/template/mod.rs";
+const LIB: &str = "src/lib.rs";
+const REGISTRY_START: &str = "register_days! {\n";
+
+/// Creates the module for a new day from the template, registers it in
+/// `lib.rs`, and creates an empty input file and example. Refuses to
+/// touch anything if the day's module already exists. Returns the paths
+/// it created or changed.
+///
+/// The sources are found from the crate root, wherever this is run from.
+pub fn new_day(day: u8, title: &str, input: &Path, examples_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let module = root.join(format!("src/day{}", day));
+    if module.exists() {
+        return Err(format!("day {} already exists in {}", day, module.display()).into());
+    }
+
+    let (template_path, lib_path) = (root.join(TEMPLATE), root.join(LIB));
+    let template = fs::read_to_string(&template_path)
+        .map_err(|e| format!("can't read template {}: {}", template_path.display(), e))?;
+    let original = fs::read_to_string(&lib_path)
+        .map_err(|e| format!("can't read {}: {}", lib_path.display(), e))?;
+    let lib = register(&original, day)?;
+
+    let source = template
+        .replace("//! Day 0", &format!("//! Day {}: {}", day, title))
+        .replace("Day0", &format!("Day{}", day))
+        .replace("day: 0", &format!("day: {}", day))
+        .replace("title: \"\"", &format!("title: {:?}", title));
+
+    // Register first, and undo it if the module can't be written, so a
+    // failure doesn't leave a day half scaffolded.
+    let mut created = Vec::new();
+    fs::write(&lib_path, lib).map_err(|e| format!("can't write {}: {}", lib_path.display(), e))?;
+    let written = fs::create_dir_all(&module)
+        .map_err(|e| format!("can't create {}: {}", module.display(), e).into())
+        .and_then(|_| write(module.join("mod.rs"), &source, &mut created));
+    if let Err(e) = written {
+        let _ = fs::remove_dir_all(&module);
+        fs::write(&lib_path, original).map_err(|e| format!("can't restore {}: {}", lib_path.display(), e))?;
+        return Err(e);
+    }
+    created.push(lib_path);
+
+    let example_dir = examples::dir(examples_dir, day);
+    fs::create_dir_all(&example_dir)?;
+    write(example_dir.join("example.txt"), "", &mut created)?;
+    write(example_dir.join("example.answers"), "", &mut created)?;
+
+    if let Some(dir) = input.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    write(input.to_path_buf(), "", &mut created)?;
+
+    Ok(created)
+}
+
+/// Writes a file unless it already exists.
+fn write(path: PathBuf, contents: &str, created: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+    if !path.exists() {
+        fs::write(&path, contents).map_err(|e| format!("can't write {}: {}", path.display(), e))?;
+        created.push(path);
+    }
+
+    Ok(())
+}
+
+/// Adds the day to the `register_days!` invocation, keeping it sorted.
+fn register(lib: &str, day: u8) -> Result<String, Box<dyn Error>> {
+    let start = lib.find(REGISTRY_START)
+        .ok_or_else(|| format!("can't find the register_days! invocation in {}", LIB))? + REGISTRY_START.len();
+    let end = start + lib[start..].find('}')
+        .ok_or_else(|| format!("unterminated register_days! invocation in {}", LIB))?;
+
+    let mut entries: Vec<(u8, String)> = lib[start..end].lines()
+        .map(|line| line.trim().trim_end_matches(','))
+        .filter(|line| !line.is_empty())
+        .map(|entry| {
+            let number = entry.strip_prefix("day")
+                .and_then(|rest| rest.split("::").next())
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(u8::MAX);
+            (number, entry.to_string())
+        })
+        .collect();
+    if entries.iter().any(|(number, _)| *number == day) {
+        return Err(format!("day {} is already registered in {}", day, LIB).into());
+    }
+    entries.push((day, format!("day{}::Day{}", day, day)));
+    entries.sort();
+
+    let block: String = entries.iter().map(|(_, entry)| format!("    {},\n", entry)).collect();
+    Ok(format!("{}{}{}", &lib[..start], block, &lib[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB_RS: &str = "use solution::register_days;\n\nregister_days! {\n    day1::Day1,\n    day10::Day10,\n    day2::Day2,\n}\n";
+
+    #[test]
+    fn test_register() {
+        let lib = register(LIB_RS, 3).unwrap();
+        assert_eq!(
+            lib,
+            "use solution::register_days;\n\nregister_days! {\n    day1::Day1,\n    day2::Day2,\n    day3::Day3,\n    day10::Day10,\n}\n",
+        );
+
+        assert!(register(LIB_RS, 2).unwrap_err().to_string().contains("already registered"));
+        assert!(register("pub mod day1;\n", 2).unwrap_err().to_string().contains("can't find"));
+    }
+}
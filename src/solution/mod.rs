@@ -0,0 +1,227 @@
+//! The traits each day implements, and the registry of days.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::input;
+
+/// Descriptive information about a day's puzzle.
+pub struct Metadata {
+    /// The day of December, 1 to 25
+    pub day: u8,
+    /// The puzzle's title
+    pub title: &'static str,
+    /// Parts whose solver doesn't produce an answer and should be skipped
+    pub unsolved: &'static [u8],
+    /// What the solver expects its input to look like
+    pub input: InputMode,
+}
+
+/// Whether a solver gets its input as read, or cleaned up first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputMode {
+    /// Without a byte order mark, with `\n` line endings, and without
+    /// trailing whitespace on any line or trailing blank lines, see
+    /// [`input::normalize`]
+    Normalized,
+    /// Exactly as read from the file
+    Raw,
+}
+
+/// A day's solution. The input is parsed once and shared by both parts.
+pub trait Solution {
+    /// The parsed input.
+    type Input;
+
+    /// Which day this is, and how to run it.
+    fn metadata(&self) -> Metadata;
+    /// Parses the puzzle input.
+    fn parse(&self, input: &str) -> Result<Self::Input, SolveError>;
+    /// Solves the first part.
+    fn part1(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+    /// Solves the second part.
+    fn part2(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+}
+
+/// An answer with the time it took to get to it.
+pub struct Timed {
+    /// The answer
+    pub answer: Answer,
+    /// Time spent parsing
+    pub parse: Duration,
+    /// Time spent solving
+    pub solve: Duration,
+}
+
+/// Object safe view of a [`Solution`], used by the registry and the runner.
+/// Solvers are shared with the runner's worker threads.
+pub trait Solver: Send + Sync {
+    /// See [`Solution::metadata`].
+    fn metadata(&self) -> Metadata;
+    /// Parses the input and solves one part.
+    fn solve(&self, part: u8, input: &str) -> Result<Answer, SolveError>;
+
+    /// Like [`Solver::solve`], timing parsing and solving separately.
+    fn solve_timed(&self, part: u8, input: &str) -> Result<Timed, SolveError>;
+
+    /// A plain function running one part, for aoc-helper's `Puzzle`. It
+    /// panics with the rendered error if the part fails.
+    fn function(&self, part: u8) -> fn(String) -> Answer;
+}
+
+impl<S> Solver for S where S: Solution + Default + Send + Sync {
+    fn metadata(&self) -> Metadata {
+        Solution::metadata(self)
+    }
+
+    fn solve(&self, part: u8, input: &str) -> Result<Answer, SolveError> {
+        let input = self.parse(&prepare(self, input))?;
+        match part {
+            1 => self.part1(&input),
+            2 => self.part2(&input),
+            _ => panic!("Unknown part!"),
+        }
+    }
+
+    fn solve_timed(&self, part: u8, input: &str) -> Result<Timed, SolveError> {
+        let input = prepare(self, input);
+        let start = Instant::now();
+        let parsed = self.parse(&input)?;
+        let parse = start.elapsed();
+
+        let start = Instant::now();
+        let answer = match part {
+            1 => self.part1(&parsed)?,
+            2 => self.part2(&parsed)?,
+            _ => panic!("Unknown part!"),
+        };
+
+        Ok(Timed { answer, parse, solve: start.elapsed() })
+    }
+
+    fn function(&self, part: u8) -> fn(String) -> Answer {
+        match part {
+            1 => solve_part::<S, 1>,
+            2 => solve_part::<S, 2>,
+            _ => panic!("Unknown part!"),
+        }
+    }
+}
+
+/// Normalizes the input unless the solution wants it raw.
+fn prepare<'a, S: Solution>(solution: &S, input: &'a str) -> Cow<'a, str> {
+    match solution.metadata().input {
+        InputMode::Normalized => input::normalize(input),
+        InputMode::Raw => Cow::Borrowed(input),
+    }
+}
+
+fn solve_part<S, const PART: u8>(input: String) -> Answer where S: Solution + Default + Send + Sync {
+    Solver::solve(&S::default(), PART, &input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// All registered solutions, keyed by day.
+pub struct Registry {
+    solutions: BTreeMap<u8, Box<dyn Solver>>,
+}
+
+impl Registry {
+    /// Panics if two solutions are for the same day, so that a mistake in
+    /// the list can't hide a day.
+    pub fn new(solutions: Vec<Box<dyn Solver>>) -> Registry {
+        let mut registered = BTreeMap::new();
+        for solution in solutions {
+            let day = solution.metadata().day;
+            if registered.insert(day, solution).is_some() {
+                panic!("day {} is registered twice", day);
+            }
+        }
+
+        Registry { solutions: registered }
+    }
+
+    /// The solver for a day, if it's registered.
+    pub fn get(&self, day: u8) -> Option<&dyn Solver> {
+        self.solutions.get(&day).map(|s| s.as_ref())
+    }
+
+    /// Every registered solver, by day.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Solver> {
+        self.solutions.values().map(|s| s.as_ref())
+    }
+}
+
+/// Declares the day modules and builds the registry from their solutions.
+/// Adding a day means adding one `module::Type` line to the invocation.
+/// The registry is built once and lives for the whole program, so solvers
+/// can be handed to worker threads.
+macro_rules! register_days {
+    ($($module:ident::$solution:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// Every registered day, built on first use.
+        pub fn registry() -> &'static $crate::solution::Registry {
+            static REGISTRY: std::sync::OnceLock<$crate::solution::Registry> = std::sync::OnceLock::new();
+            REGISTRY.get_or_init(|| $crate::solution::Registry::new(vec![
+                $(Box::new($module::$solution)),*
+            ]))
+        }
+    };
+}
+
+pub(crate) use register_days;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers with its input as it was handed over, or with its length.
+    #[derive(Default)]
+    struct Echo<const DAY: u8, const RAW: bool = false>;
+
+    impl<const DAY: u8, const RAW: bool> Solution for Echo<DAY, RAW> {
+        type Input = String;
+
+        fn metadata(&self) -> Metadata {
+            let input = if RAW { InputMode::Raw } else { InputMode::Normalized };
+            Metadata { day: DAY, title: "Echo", unsolved: &[], input }
+        }
+
+        fn parse(&self, input: &str) -> Result<String, SolveError> {
+            Ok(input.to_string())
+        }
+
+        fn part1(&self, input: &String) -> Result<Answer, SolveError> {
+            Ok(input.as_str().into())
+        }
+
+        fn part2(&self, input: &String) -> Result<Answer, SolveError> {
+            Ok(input.len().into())
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry = Registry::new(vec![Box::new(Echo::<2>), Box::new(Echo::<1>)]);
+        let days: Vec<u8> = registry.iter().map(|s| s.metadata().day).collect();
+        assert_eq!(days, [1, 2]);
+        assert!(registry.get(3).is_none());
+    }
+
+    #[test]
+    fn test_input_mode() {
+        let input = "\u{feff}ab \r\ncd\r\n\n";
+        assert_eq!(Echo::<1>.solve(1, input).unwrap(), Answer::from("ab\ncd"));
+        assert_eq!(Echo::<1, true>.solve(1, input).unwrap(), Answer::from(input));
+        assert_eq!(Echo::<1, true>.solve_timed(2, input).unwrap().answer, Answer::from(input.len()));
+    }
+
+    #[test]
+    #[should_panic(expected = "day 1 is registered twice")]
+    fn test_registry_duplicate_day() {
+        Registry::new(vec![Box::new(Echo::<1>), Box::new(Echo::<2>), Box::new(Echo::<1>)]);
+    }
+}
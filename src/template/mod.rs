@@ -1,15 +1,36 @@
-pub fn solver(star: u8) -> fn(String) -> i128 {
-    match star {
-        1 => star1,
-        2 => star2,
-        _ => panic!("Unknown star!"),
+//! Day 0
+
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solution::{InputMode, Metadata, Solution};
+
+#[derive(Default)]
+pub struct Day0;
+
+impl Solution for Day0 {
+    type Input = String;
+
+    fn metadata(&self) -> Metadata {
+        Metadata { day: 0, title: "", unsolved: &[], input: InputMode::Normalized }
+    }
+
+    fn parse(&self, input: &str) -> Result<String, SolveError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, input: &String) -> Result<Answer, SolveError> {
+        Ok(star1(input))
+    }
+
+    fn part2(&self, input: &String) -> Result<Answer, SolveError> {
+        Ok(star2(input))
     }
 }
 
-fn star1(input: String) -> i128 {
-    0
+fn star1(_input: &str) -> Answer {
+    0.into()
 }
 
-fn star2(input: String) -> i128 {
-    0
+fn star2(_input: &str) -> Answer {
+    0.into()
 }
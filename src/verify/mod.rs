@@ -0,0 +1,250 @@
+//! Checking answers against a file of known answers.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::answer::Answer;
+use crate::runner::{Outcome, Status};
+
+/// The comment the answers file starts with when saved.
+const HEADER: &str = "\
+# Known answers, one `day part answer` line per part.
+# Add the answers of newly solved parts with `verify --record`.
+";
+
+/// Answers known to be correct, keyed by day and part. The file has one
+/// `day part answer` line per part, and lines starting with `#` are
+/// comments.
+pub struct KnownAnswers {
+    path: PathBuf,
+    answers: BTreeMap<(u8, u8), Answer>,
+}
+
+impl KnownAnswers {
+    /// Loads the answers file, or starts an empty one if it doesn't exist.
+    pub fn load(path: &Path) -> Result<KnownAnswers, Box<dyn Error>> {
+        let mut known = KnownAnswers { path: path.to_path_buf(), answers: BTreeMap::new() };
+        if !path.exists() {
+            return Ok(known);
+        }
+
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("can't read answers file {}: {}", path.display(), e))?;
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let ((day, part), answer) = parse_line(line)
+                .ok_or_else(|| format!("{}:{}: expected `day part answer`, got `{}`", path.display(), i + 1, line))?;
+            known.answers.insert((day, part), answer);
+        }
+
+        Ok(known)
+    }
+
+    /// The known answer to a part.
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    /// Sets the known answer to a part, replacing any there was.
+    pub fn insert(&mut self, day: u8, part: u8, answer: Answer) {
+        self.answers.insert((day, part), answer);
+    }
+
+    /// Takes the answers of the checks without a known answer as the
+    /// known ones, which they then match.
+    pub fn record(&mut self, checks: &mut [Check]) {
+        for c in checks.iter_mut() {
+            if let Verdict::Missing(answer) = &c.verdict {
+                self.insert(c.day, c.part, answer.clone());
+                c.verdict = Verdict::Match;
+            }
+        }
+    }
+
+    /// Writes the answers back to the file they were loaded from.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let mut text = String::from(HEADER);
+        for ((day, part), answer) in self.answers.iter() {
+            text.push_str(&format!("{} {} {}\n", day, part, answer.serialize()));
+        }
+
+        fs::write(&self.path, text)
+            .map_err(|e| format!("can't write answers file {}: {}", self.path.display(), e).into())
+    }
+}
+
+fn parse_line(line: &str) -> Option<((u8, u8), Answer)> {
+    let mut fields = line.splitn(3, ' ');
+    let day = fields.next()?.parse().ok()?;
+    let part = fields.next()?.parse().ok()?;
+    let answer = fields.next()?.parse().ok()?;
+
+    Some(((day, part), answer))
+}
+
+/// How a part's outcome compares with its known answer.
+pub enum Verdict {
+    /// The answer is the known one
+    Match,
+    /// The answer differs from the known one
+    Mismatch {
+        /// The known answer
+        expected: Answer,
+        /// The answer the solver gave
+        actual: Answer,
+    },
+    /// The part was solved, but there's no known answer to compare with
+    Missing(Answer),
+    /// The solver returned an error or panicked
+    Failed(String),
+    /// The part is unsolved or has no input
+    Skipped(&'static str),
+}
+
+/// The verdict on one part.
+pub struct Check {
+    /// The day
+    pub day: u8,
+    /// The part, 1 or 2
+    pub part: u8,
+    /// How the answer compares with the expected one
+    pub verdict: Verdict,
+}
+
+impl Check {
+    /// Whether the check should fail the run. Panics count, since a solver
+    /// that no longer finishes has regressed as much as a wrong answer.
+    pub fn failed(&self) -> bool {
+        self.verdict.failed()
+    }
+}
+
+impl Verdict {
+    /// Compares the outcome of running a part with the expected answer.
+    pub fn judge(status: Status, expected: Option<&Answer>) -> Verdict {
+        match status {
+            Status::Solved(actual) => match expected {
+                Some(expected) if *expected == actual => Verdict::Match,
+                Some(expected) => Verdict::Mismatch { expected: expected.clone(), actual },
+                None => Verdict::Missing(actual),
+            },
+            Status::Error(e) => Verdict::Failed(e.to_string()),
+            Status::Panicked(message) => Verdict::Failed(format!("panicked: {}", message)),
+            Status::TimedOut(budget) => Verdict::Failed(format!("timed out after {:.2?}", budget)),
+            // The answer was recorded from an input that has since gone missing
+            Status::NoInput if expected.is_some() => Verdict::Failed("no input for the known answer".to_string()),
+            Status::NoInput => Verdict::Skipped("no input"),
+            Status::Unimplemented => Verdict::Skipped("unimplemented"),
+        }
+    }
+
+    /// The verdict on one line.
+    pub fn describe(&self) -> String {
+        match self {
+            Verdict::Match => "match".to_string(),
+            Verdict::Mismatch { expected, actual } => format!(
+                "MISMATCH, expected {} but got {}", expected.serialize(), actual.serialize(),
+            ),
+            Verdict::Missing(actual) => format!("no known answer, got {}", actual.serialize()),
+            Verdict::Failed(message) => format!("FAILED: {}", message),
+            Verdict::Skipped(reason) => format!("skipped, {}", reason),
+        }
+    }
+
+    /// Whether the part fails verification. A solved part without a known
+    /// answer fails too, since nothing vouches for it.
+    pub fn failed(&self) -> bool {
+        matches!(self, Verdict::Mismatch { .. } | Verdict::Failed(_) | Verdict::Missing(_))
+    }
+
+    /// Whether the part went wrong: a wrong answer, or no answer at all.
+    /// Unlike [`Verdict::failed`], a part without a known answer is fine.
+    pub fn wrong(&self) -> bool {
+        matches!(self, Verdict::Mismatch { .. } | Verdict::Failed(_))
+    }
+
+    /// Whether an answer was compared with a known one.
+    pub fn compared(&self) -> bool {
+        matches!(self, Verdict::Match | Verdict::Mismatch { .. })
+    }
+}
+
+/// Whether verification passes: something was compared with a known
+/// answer, and no part failed.
+pub fn passed(checks: &[Check]) -> bool {
+    checks.iter().any(|c| c.verdict.compared()) && !checks.iter().any(Check::failed)
+}
+
+/// Judges each outcome against the known answers.
+pub fn check(outcomes: Vec<Outcome>, known: &KnownAnswers) -> Vec<Check> {
+    outcomes.into_iter()
+        .map(|o| {
+            let verdict = Verdict::judge(o.status, known.get(o.day, o.part));
+            Check { day: o.day, part: o.part, verdict }
+        })
+        .collect()
+}
+
+/// Prints a line per check and a summary.
+pub fn print_report(checks: &[Check]) {
+    for c in checks {
+        println!("Day {:>2} part {}: {}", c.day, c.part, c.verdict.describe());
+    }
+
+    let count = |f: fn(&Verdict) -> bool| checks.iter().filter(|c| f(&c.verdict)).count();
+    println!(
+        "{} matched, {} mismatched, {} failed, {} without a known answer, {} skipped",
+        count(|v| matches!(v, Verdict::Match)),
+        count(|v| matches!(v, Verdict::Mismatch { .. })),
+        count(|v| matches!(v, Verdict::Failed(_))),
+        count(|v| matches!(v, Verdict::Missing(_))),
+        count(|v| matches!(v, Verdict::Skipped(_))),
+    );
+
+    if !checks.iter().any(|c| c.verdict.compared()) {
+        println!("Nothing was compared with a known answer");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn checks(verdicts: Vec<Verdict>) -> Vec<Check> {
+        verdicts.into_iter().map(|verdict| Check { day: 1, part: 1, verdict }).collect()
+    }
+
+    #[test]
+    fn test_passed() {
+        assert!(passed(&checks(vec![Verdict::Match, Verdict::Skipped("no input")])));
+        assert!(!passed(&checks(vec![Verdict::Match, Verdict::Missing(Answer::from(1))])));
+        assert!(!passed(&checks(vec![Verdict::Match, Verdict::Mismatch { expected: 1.into(), actual: 2.into() }])));
+        assert!(!passed(&checks(vec![Verdict::Skipped("no input"), Verdict::Skipped("unimplemented")])));
+        assert!(!passed(&checks(vec![])));
+    }
+
+    #[test]
+    fn test_check() {
+        let mut known = KnownAnswers { path: PathBuf::new(), answers: BTreeMap::new() };
+        known.insert(1, 1, 7.into());
+        let outcome = |part, status| Outcome { day: 1, part, status, source: None, elapsed: Duration::ZERO, parse: None, solve: None, memory: None };
+        let checks = check(vec![
+            outcome(1, Status::Solved(7.into())),
+            outcome(1, Status::NoInput),
+            outcome(2, Status::NoInput),
+        ], &known);
+
+        assert!(matches!(checks[0].verdict, Verdict::Match));
+        assert!(checks[1].failed());
+        assert!(matches!(checks[2].verdict, Verdict::Skipped("no input")));
+    }
+}
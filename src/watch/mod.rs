@@ -0,0 +1,114 @@
+//! Polling files for changes, for re-running a day as its files change.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::examples;
+use crate::input::{Input, Inputs, Source};
+use crate::runner;
+use crate::solution::Solver;
+
+/// The modification time and length of each watched file, `None` for
+/// files that don't exist.
+#[derive(PartialEq)]
+pub struct Snapshot(BTreeMap<PathBuf, Option<(SystemTime, u64)>>);
+
+impl Snapshot {
+    /// Looks at the given files, and every file directly in the given
+    /// directories, so that added and removed files count as changes.
+    pub fn take(files: &[&Path], dirs: &[&Path]) -> Snapshot {
+        let mut stamps = BTreeMap::new();
+        for file in files {
+            stamps.insert(file.to_path_buf(), stamp(file));
+        }
+
+        for dir in dirs {
+            let entries = fs::read_dir(dir).into_iter().flatten().flatten();
+            for path in entries.map(|e| e.path()).filter(|p| p.is_file()) {
+                let s = stamp(&path);
+                stamps.insert(path, s);
+            }
+        }
+
+        Snapshot(stamps)
+    }
+}
+
+/// Runs a day round after round, remembering the answers of the last
+/// round to point out the ones that change.
+pub struct Watcher {
+    solver: &'static dyn Solver,
+    parts: Vec<u8>,
+    budget: Option<Duration>,
+    answers: HashMap<(String, u8), String>,
+}
+
+impl Watcher {
+    /// Watches `parts` of a day, remembering no answers yet.
+    pub fn new(solver: &'static dyn Solver, parts: Vec<u8>, budget: Option<Duration>) -> Watcher {
+        Watcher { solver, parts, budget, answers: HashMap::new() }
+    }
+
+    /// Runs the parts against the input and every example, printing the
+    /// answers and marking those that differ from the previous round. An
+    /// input or examples that can't be read are reported and left out.
+    pub fn round(&mut self, inputs: &Inputs, input: Option<&Path>, examples_dir: &Path) {
+        let day = self.solver.metadata().day;
+        let mut runs = Vec::new();
+        match inputs.load(day, input) {
+            Ok(input) => runs.push(("input".to_string(), input)),
+            Err(e) => eprintln!("Skipping the input: {}", e),
+        }
+        match examples::load(examples_dir, day) {
+            Ok(examples) => runs.extend(examples.into_iter().map(|e| {
+                (e.name, Input { text: e.text, source: Source::File(e.path) })
+            })),
+            Err(e) => eprintln!("Skipping the examples: {}", e),
+        }
+
+        for (name, input) in runs {
+            for &part in &self.parts {
+                let outcome = runner::quietly(|| runner::run_part(self.solver, part, Some(input.clone()), self.budget));
+                let answer = outcome.status.describe();
+                let change = match self.answers.insert((name.clone(), part), answer.clone()) {
+                    None => String::new(),
+                    Some(previous) if previous == answer => "  (unchanged)".to_string(),
+                    Some(previous) => format!("  ** CHANGED, was {}", previous),
+                };
+                println!("Day {} part {} {} ({:.2?}): {}{}", day, part, name, outcome.elapsed, answer, change);
+            }
+        }
+    }
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let dir = std::env::temp_dir().join(format!("aoc2021-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let outside = dir.with_extension("txt");
+        let snapshot = || Snapshot::take(&[&outside], &[&dir]);
+
+        let empty = snapshot();
+        fs::write(dir.join("example.txt"), "1\n2").unwrap();
+        let added = snapshot();
+        assert!(empty != added);
+        assert!(added == snapshot());
+
+        fs::write(&outside, "3").unwrap();
+        assert!(added != snapshot());
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_file(&outside).unwrap();
+    }
+}